@@ -14,5 +14,19 @@ fn main() {
         )
         .expect("Failed to create placeholder.txt");
     }
+
+    // Embed the gateway hash manifest written by scripts/bundle-gateway.sh so
+    // verify_install_integrity can check the bundle against it. Dev builds
+    // have no bundle, so they get an empty manifest.
+    let manifest = std::path::Path::new("resources/gateway-manifest.sha256");
+    println!("cargo:rerun-if-changed={}", manifest.display());
+    let contents = std::fs::read_to_string(manifest).unwrap_or_default();
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    std::fs::write(
+        std::path::Path::new(&out_dir).join("gateway-manifest.sha256"),
+        contents,
+    )
+    .expect("Failed to write embedded gateway manifest");
+
     tauri_build::build()
 }
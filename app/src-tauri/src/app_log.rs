@@ -0,0 +1,117 @@
+//! The app's own log. `tracing` events go to stdout and, once `open_log_file`
+//! has run, to `~/.moose/logs/app.log` as well, so they survive in release
+//! builds for bug reports.
+//!
+//! Verbosity follows `RUST_LOG` (e.g. `RUST_LOG=tauri_app_lib=debug`), and
+//! defaults to info for the app and the gateway's output, warnings for
+//! dependencies. Gateway output only goes to stdout here; it has its own file
+//! (`gateway_log`).
+
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Runtime};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+const LOG_FILENAME: &str = "app.log";
+/// Past this size, app.log is moved to app.log.1 (replacing the old one) on
+/// the next launch.
+const LOG_MAX_BYTES: u64 = 5 * 1024 * 1024;
+const DEFAULT_FILTER: &str = "warn,tauri_app_lib=info,gateway=info";
+/// Target of the gateway's echoed stdout/stderr lines.
+pub(crate) const GATEWAY_TARGET: &str = "gateway";
+
+static LOG_FILE: OnceLock<Mutex<File>> = OnceLock::new();
+
+/// Writes to app.log once it's open, and drops output until then.
+struct LogFileWriter;
+
+impl Write for LogFileWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match LOG_FILE.get().map(|file| file.lock()) {
+            Some(Ok(mut file)) => file.write(buf),
+            _ => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match LOG_FILE.get().map(|file| file.lock()) {
+            Some(Ok(mut file)) => file.flush(),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Installs the subscriber. Called first thing in `run()`.
+pub(crate) fn init() {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    let stdout = tracing_subscriber::fmt::layer();
+    let file = tracing_subscriber::fmt::layer()
+        .with_ansi(false)
+        .with_writer(|| LogFileWriter)
+        .with_filter(tracing_subscriber::filter::filter_fn(|meta| {
+            meta.target() != GATEWAY_TARGET
+        }));
+    let _ = tracing_subscriber::registry()
+        .with(filter)
+        .with(stdout)
+        .with(file)
+        .try_init();
+}
+
+pub(crate) fn log_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    Ok(crate::get_moose_dir(app)?.join("logs").join(LOG_FILENAME))
+}
+
+/// Starts copying the log to app.log, once the data directory is known.
+/// Best effort: without the file, logging to stdout carries on.
+pub(crate) fn open_log_file<R: Runtime>(app: &AppHandle<R>) {
+    let opened = log_path(app).and_then(|path| open(&path).map_err(|e| e.to_string()));
+    match opened {
+        Ok(file) => {
+            let _ = LOG_FILE.set(Mutex::new(file));
+        }
+        Err(e) => tracing::warn!("App log file disabled: {}", e),
+    }
+}
+
+fn open(path: &std::path::Path) -> std::io::Result<File> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    if std::fs::metadata(path).is_ok_and(|m| m.len() > LOG_MAX_BYTES) {
+        std::fs::rename(path, path.with_extension("log.1"))?;
+    }
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_rotates_oversized_log() {
+        let dir = std::env::temp_dir().join("openmoose-app-log-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join(LOG_FILENAME);
+
+        let mut file = open(&path).unwrap();
+        file.write_all(&vec![b'x'; LOG_MAX_BYTES as usize + 1])
+            .unwrap();
+        drop(file);
+        let mut file = open(&path).unwrap();
+        file.write_all(b"fresh\n").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "fresh\n");
+        let rotated = std::fs::metadata(path.with_extension("log.1")).unwrap();
+        assert_eq!(rotated.len(), LOG_MAX_BYTES + 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,150 @@
+//! Timestamped snapshots of config.json in `backups/` next to it, taken by hand
+//! (`backup_config`) or automatically before risky config changes.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Runtime};
+
+use crate::error::AppError;
+
+/// Older backups beyond this many are pruned after each new one.
+const MAX_CONFIG_BACKUPS: usize = 10;
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct ConfigBackup {
+    pub name: String,
+    pub created_epoch_millis: u64,
+    pub size: u64,
+}
+
+fn backups_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    Ok(crate::get_config_dir(app)?.join("backups"))
+}
+
+/// Timestamp of a `config-<millis>.json` backup name, or `None` for anything
+/// else (which also rejects paths smuggled into `restore_config`).
+fn parse_backup_name(name: &str) -> Option<u64> {
+    name.strip_prefix("config-")?
+        .strip_suffix(".json")?
+        .parse()
+        .ok()
+}
+
+/// All backups, newest first.
+fn read_backups<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<ConfigBackup>, String> {
+    let dir = backups_dir(app)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups: Vec<ConfigBackup> = std::fs::read_dir(&dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            let created_epoch_millis = parse_backup_name(&name)?;
+            Some(ConfigBackup {
+                name,
+                created_epoch_millis,
+                size: entry.metadata().ok()?.len(),
+            })
+        })
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_epoch_millis));
+    Ok(backups)
+}
+
+/// Copies config.json into the backups directory and prunes old copies.
+/// Returns `None` when there's no config to back up yet.
+pub(crate) fn backup_config_internal<R: Runtime>(
+    app: &AppHandle<R>,
+) -> Result<Option<ConfigBackup>, String> {
+    let config_path = crate::get_config_path(app)?;
+    if !config_path.exists() {
+        return Ok(None);
+    }
+    let dir = backups_dir(app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let created_epoch_millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let name = format!("config-{}.json", created_epoch_millis);
+    let size = std::fs::copy(&config_path, dir.join(&name))
+        .map_err(|e| format!("Failed to back up config: {}", e))?;
+
+    for old in read_backups(app)?.iter().skip(MAX_CONFIG_BACKUPS) {
+        let _ = std::fs::remove_file(dir.join(&old.name));
+    }
+    tracing::info!("Backed up config to {}", name);
+    Ok(Some(ConfigBackup {
+        name,
+        created_epoch_millis,
+        size,
+    }))
+}
+
+#[tauri::command]
+pub(crate) async fn backup_config(app: tauri::AppHandle) -> Result<Option<ConfigBackup>, AppError> {
+    backup_config_internal(&app).map_err(AppError::Io)
+}
+
+#[tauri::command]
+pub(crate) async fn list_config_backups(
+    app: tauri::AppHandle,
+) -> Result<Vec<ConfigBackup>, AppError> {
+    read_backups(&app).map_err(AppError::Io)
+}
+
+/// Replaces config.json with a backup. The current config is backed up first,
+/// so a restore can itself be undone.
+#[tauri::command]
+pub(crate) async fn restore_config(
+    app: tauri::AppHandle,
+    backup_name: String,
+) -> Result<(), AppError> {
+    if parse_backup_name(&backup_name).is_none() {
+        return Err(AppError::NotFound(format!(
+            "\"{}\" is not a config backup",
+            backup_name
+        )));
+    }
+    let source = backups_dir(&app)?.join(&backup_name);
+    let content = std::fs::read_to_string(&source)
+        .map_err(|e| AppError::Io(format!("Failed to read backup {}: {}", backup_name, e)))?;
+    let value: serde_json::Value = serde_json::from_str(&content).map_err(|e| {
+        AppError::Config(format!("Backup {} is not valid JSON: {}", backup_name, e))
+    })?;
+    if !value.is_object() {
+        return Err(AppError::Config(format!(
+            "Backup {} is not a JSON object",
+            backup_name
+        )));
+    }
+
+    backup_config_internal(&app).map_err(AppError::Io)?;
+    let config_path = crate::get_config_path(&app)?;
+    let staging = config_path.with_extension("json.restore");
+    std::fs::write(&staging, &content)?;
+    crate::backup_config_file(&config_path).map_err(AppError::Io)?;
+    std::fs::rename(&staging, &config_path)
+        .map_err(|e| AppError::Io(format!("Failed to restore config: {}", e)))?;
+    tracing::info!("Restored config from {}", backup_name);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_backup_name() {
+        assert_eq!(
+            parse_backup_name("config-1760000000000.json"),
+            Some(1_760_000_000_000)
+        );
+        assert_eq!(parse_backup_name("config-../../etc/passwd.json"), None);
+        assert_eq!(parse_backup_name("config.json"), None);
+    }
+}
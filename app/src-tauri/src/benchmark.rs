@@ -0,0 +1,184 @@
+//! Quick inference benchmark against the running gateway (`benchmark_inference`).
+//!
+//! The gateway only serves inference over its WebSocket, so this drives a
+//! single `agent.run` the same way the UI does and times the `agent.delta`
+//! stream that comes back.
+
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tauri::State;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::error::AppError;
+use crate::GatewayState;
+
+const BENCHMARK_PROMPT: &str =
+    "Count from one to twenty in words, separated by commas. Reply with the list only.";
+/// Stop timing after this many deltas; the numbers don't get any better past it.
+const BENCHMARK_MAX_TOKENS: u32 = 64;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Covers the whole run, including the model's first-token latency.
+const BENCHMARK_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct BenchmarkResult {
+    /// Time from sending the prompt to the first streamed delta.
+    pub ttft_ms: u64,
+    /// Decode rate after the first token. Each `agent.delta` counts as one token.
+    pub tokens_per_sec: f64,
+    pub total_ms: u64,
+}
+
+/// The subset of gateway WebSocket messages the benchmark cares about.
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum GatewayMessage {
+    #[serde(rename = "agent.delta")]
+    Delta,
+    #[serde(rename = "agent.final")]
+    Final,
+    #[serde(rename = "error")]
+    Error { message: String },
+    #[serde(other)]
+    Other,
+}
+
+/// Sends `BENCHMARK_PROMPT` to the gateway on `port` and times the reply.
+pub(crate) async fn run_benchmark(port: u16, timeout: Duration) -> Result<BenchmarkResult, String> {
+    let url = format!("ws://127.0.0.1:{}", port);
+    let (mut ws, _) = tokio::time::timeout(CONNECT_TIMEOUT, tokio_tungstenite::connect_async(url))
+        .await
+        .map_err(|_| "Timed out connecting to the gateway".to_string())?
+        .map_err(|e| format!("Failed to connect to the gateway: {}", e))?;
+
+    let request = serde_json::json!({ "type": "agent.run", "message": BENCHMARK_PROMPT });
+    let started = Instant::now();
+    ws.send(Message::text(request.to_string()))
+        .await
+        .map_err(|e| format!("Failed to send benchmark prompt: {}", e))?;
+
+    let deadline = tokio::time::Instant::from_std(started + timeout);
+    let mut first_token: Option<Instant> = None;
+    let mut tokens = 0u32;
+    while tokens < BENCHMARK_MAX_TOKENS {
+        let frame = match tokio::time::timeout_at(deadline, ws.next()).await {
+            Err(_) if first_token.is_none() => {
+                return Err(format!(
+                    "Gateway produced no tokens within {}s",
+                    timeout.as_secs()
+                ))
+            }
+            Err(_) => break,
+            Ok(None) => return Err("Gateway closed the connection mid-benchmark".to_string()),
+            Ok(Some(frame)) => frame.map_err(|e| format!("Gateway connection failed: {}", e))?,
+        };
+        let Message::Text(text) = frame else {
+            continue;
+        };
+        match serde_json::from_str::<GatewayMessage>(&text) {
+            Ok(GatewayMessage::Delta) => {
+                first_token.get_or_insert_with(Instant::now);
+                tokens += 1;
+            }
+            Ok(GatewayMessage::Final) => break,
+            Ok(GatewayMessage::Error { message }) => {
+                return Err(format!("Gateway rejected the benchmark: {}", message))
+            }
+            Ok(GatewayMessage::Other) | Err(_) => {}
+        }
+    }
+    let finished = Instant::now();
+    let _ = ws.close(None).await;
+
+    let first_token = first_token.ok_or("Gateway finished without streaming any tokens")?;
+    // The first delta is what TTFT measures, so the rate covers the rest.
+    let decode_secs = finished.duration_since(first_token).as_secs_f64();
+    let tokens_per_sec = if tokens > 1 && decode_secs > 0.0 {
+        (tokens - 1) as f64 / decode_secs
+    } else {
+        0.0
+    };
+
+    Ok(BenchmarkResult {
+        ttft_ms: first_token.duration_since(started).as_millis() as u64,
+        tokens_per_sec,
+        total_ms: finished.duration_since(started).as_millis() as u64,
+    })
+}
+
+/// Runs a short fixed generation on the running gateway and reports
+/// time-to-first-token and decode speed. Note the prompt goes through the
+/// normal agent path, so it shows up in chat history. The outcome is recorded
+/// in the load history.
+#[tauri::command]
+pub(crate) async fn benchmark_inference(
+    app: tauri::AppHandle,
+    state: State<'_, GatewayState>,
+) -> Result<BenchmarkResult, AppError> {
+    let status = crate::gateway_status_internal(&app, &state).map_err(AppError::Gateway)?;
+    if !status.running {
+        return Err(AppError::Gateway("Gateway is not running".to_string()));
+    }
+    tracing::info!("Benchmarking inference on port {}", status.port);
+    let result = run_benchmark(status.port, BENCHMARK_TIMEOUT).await;
+    crate::load_history::record_load_outcome(
+        &app,
+        result.as_ref().map(|_| ()).map_err(String::as_str),
+    );
+    let result = result.map_err(AppError::Gateway)?;
+    tracing::info!(
+        "Benchmark: ttft {}ms, {:.1} tok/s, total {}ms",
+        result.ttft_ms,
+        result.tokens_per_sec,
+        result.total_ms
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn spawn_ws_server(replies: &'static [&'static str]) -> u16 {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let _request = ws.next().await;
+            for reply in replies {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                let _ = ws.send(Message::text(*reply)).await;
+            }
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn test_run_benchmark_times_stream() {
+        let port = spawn_ws_server(&[
+            r#"{"type":"agent.delta","text":"one"}"#,
+            r#"{"type":"agent.tool_call","name":"x"}"#,
+            r#"{"type":"agent.delta","text":", two"}"#,
+            r#"{"type":"agent.delta","text":", three"}"#,
+            r#"{"type":"agent.final"}"#,
+        ])
+        .await;
+
+        let result = run_benchmark(port, Duration::from_secs(5)).await.unwrap();
+        assert!(result.ttft_ms <= result.total_ms);
+        assert!(result.tokens_per_sec > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_run_benchmark_reports_gateway_error() {
+        let port =
+            spawn_ws_server(&[r#"{"type":"error","message":"Brain is still warming up."}"#]).await;
+
+        let err = run_benchmark(port, Duration::from_secs(5))
+            .await
+            .unwrap_err();
+        assert!(err.contains("warming up"), "{}", err);
+    }
+}
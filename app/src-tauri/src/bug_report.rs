@@ -0,0 +1,224 @@
+//! Copy-pasteable Markdown summary of the app's state for GitHub issues
+//! (`generate_bug_report`). Secrets are scrubbed from everything included.
+
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use tauri::{Manager, State};
+
+use crate::error::AppError;
+use crate::{GatewayState, GatewayStats};
+
+/// Lines kept from the end of each log file.
+const LOG_TAIL_LINES: usize = 20;
+const REDACTED: &str = "[redacted]";
+
+/// Redacts sensitive keys at any depth (profiles carry their own env).
+fn redact_config(value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let value = if crate::is_sensitive_key(key) {
+                        serde_json::Value::String(REDACTED.to_string())
+                    } else {
+                        redact_config(value)
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+        ),
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.iter().map(redact_config).collect())
+        }
+        other => other.clone(),
+    }
+}
+
+/// Drops log lines that mention a credential and masks bare HuggingFace
+/// tokens (`hf_...`) wherever they appear.
+fn scrub_secrets(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if crate::is_sensitive_key(line) {
+                return REDACTED.to_string();
+            }
+            line.split(' ')
+                .map(|word| {
+                    if word.starts_with("hf_") && word.len() > 20 {
+                        REDACTED
+                    } else {
+                        word
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn tail(path: &Path, lines: usize) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let all: Vec<&str> = content.lines().collect();
+    Some(all[all.len().saturating_sub(lines)..].join("\n"))
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+/// Builds a Markdown bug report: app and system info, redacted config, model
+/// state, gateway status and last crash, and the tail of each log file.
+#[tauri::command]
+pub(crate) async fn generate_bug_report(
+    app: tauri::AppHandle,
+    state: State<'_, GatewayState>,
+) -> Result<String, AppError> {
+    let mut out = String::new();
+    let _ = writeln!(out, "## OpenMoose bug report\n");
+
+    let (node_path, node_source) = crate::resolve_node(&app);
+    let _ = writeln!(out, "### System\n");
+    let _ = writeln!(out, "- App version: {}", app.package_info().version);
+    let _ = writeln!(
+        out,
+        "- OS: {} ({})",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    let _ = writeln!(out, "- Node.js: `{}` ({:?})\n", node_path, node_source);
+
+    let _ = writeln!(out, "### Config\n");
+    let config = match crate::read_config_raw(&app) {
+        Ok(raw) => serde_json::to_string_pretty(&redact_config(&raw)).unwrap_or_default(),
+        Err(e) => format!("unreadable: {}", e),
+    };
+    let _ = writeln!(out, "```json\n{}\n```\n", config);
+
+    let _ = writeln!(out, "### Model\n");
+    let model = crate::active_model(&app);
+    let model_path = crate::model_path(&app, model)?;
+    let model_size = model_path.metadata().map(|m| m.len()).ok();
+    let _ = writeln!(out, "- Model: {} (`{}`)", model.id, model.filename);
+    match model_size {
+        Some(size) => {
+            let _ = writeln!(out, "- Size: {} bytes (minimum {})", size, model.min_size);
+        }
+        None => {
+            let _ = writeln!(out, "- Size: not downloaded");
+        }
+    }
+    let _ = writeln!(
+        out,
+        "- Partial download in progress: {}",
+        yes_no(crate::download_meta_path(&model_path).exists())
+    );
+    let _ = writeln!(
+        out,
+        "- Loaded successfully before: {}",
+        yes_no(crate::load_history::current_model_known_good(&app))
+    );
+    if let Some(dir) = model_path.parent() {
+        if let Some(warning) = crate::storage::check_filesystem(dir).warning {
+            let _ = writeln!(out, "- Location warning: {}", warning);
+        }
+    }
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "### Gateway\n");
+    match crate::gateway_status_internal(&app, &state) {
+        Ok(status) => {
+            let _ = writeln!(
+                out,
+                "- Running: {}{}",
+                yes_no(status.running),
+                status
+                    .pid
+                    .map(|pid| format!(" (pid {})", pid))
+                    .unwrap_or_default()
+            );
+            let _ = writeln!(out, "- Port: {}", status.port);
+            let _ = writeln!(
+                out,
+                "- Restarts: {}, crashes: {}",
+                status.restart_count, status.crash_count
+            );
+        }
+        Err(e) => {
+            let _ = writeln!(out, "- Status unavailable: {}", e);
+        }
+    }
+    let stats = app.state::<GatewayStats>();
+    let last_crash = stats.last_crash.lock().ok().and_then(|c| c.clone());
+    match last_crash {
+        Some(crash) => {
+            let _ = writeln!(
+                out,
+                "- Last crash: pid {}, exit code {}",
+                crash.pid,
+                crash
+                    .exit_code
+                    .map(|c| c.to_string())
+                    .unwrap_or_else(|| "none (killed by signal)".to_string())
+            );
+        }
+        None if stats.crashes.load(Ordering::SeqCst) == 0 => {
+            let _ = writeln!(out, "- Last crash: none");
+        }
+        None => {}
+    }
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "### Recent logs\n");
+    let logs_dir = crate::get_moose_dir(&app)?.join("logs");
+    let mut log_files: Vec<_> = std::fs::read_dir(&logs_dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    log_files.retain(|path| path.is_file());
+    log_files.sort();
+    if log_files.is_empty() {
+        let _ = writeln!(out, "No log files in {}", logs_dir.display());
+    }
+    for path in log_files {
+        let Some(lines) = tail(&path, LOG_TAIL_LINES) else {
+            continue;
+        };
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let _ = writeln!(
+            out,
+            "#### {}\n\n```\n{}\n```\n",
+            name,
+            scrub_secrets(&lines)
+        );
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redaction_and_scrubbing() {
+        let config = serde_json::json!({
+            "theme": "dark",
+            "profiles": { "work": { "env": { "HF_TOKEN": "hf_secret" } } }
+        });
+        let redacted = redact_config(&config);
+        assert_eq!(redacted["theme"], "dark");
+        assert_eq!(redacted["profiles"]["work"]["env"]["HF_TOKEN"], REDACTED);
+
+        let log =
+            "fetching model\nAuthorization: Bearer abc\nusing hf_abcdefghijklmnopqrstuvwxyz now";
+        assert_eq!(
+            scrub_secrets(log),
+            "fetching model\n[redacted]\nusing [redacted] now"
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! Which optional gateway endpoints the running gateway version serves
+//! (`probe_gateway_capabilities`), so the UI only offers features it supports.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::GatewayState;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub(crate) struct GatewayCapabilities {
+    pub health: bool,
+    pub info: bool,
+    pub loglevel: bool,
+    pub completion: bool,
+    /// The WebSocket that chat and `benchmark_inference` use.
+    pub websocket: bool,
+}
+
+/// Capabilities of the gateway process with the given PID, so probing happens
+/// once per gateway start.
+#[derive(Default)]
+pub(crate) struct CapabilityCache(Mutex<Option<(u32, GatewayCapabilities)>>);
+
+/// An endpoint exists unless the gateway says 404; a 405 or 400 just means
+/// a plain GET isn't how it's meant to be used.
+async fn endpoint_exists(client: &reqwest::Client, port: u16, path: &str) -> bool {
+    client
+        .get(format!("http://127.0.0.1:{}{}", port, path))
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .map(|res| res.status() != reqwest::StatusCode::NOT_FOUND)
+        .unwrap_or(false)
+}
+
+async fn websocket_accepts(port: u16) -> bool {
+    let connect = tokio_tungstenite::connect_async(format!("ws://127.0.0.1:{}", port));
+    match tokio::time::timeout(PROBE_TIMEOUT, connect).await {
+        Ok(Ok((mut ws, _))) => {
+            let _ = ws.close(None).await;
+            true
+        }
+        _ => false,
+    }
+}
+
+pub(crate) async fn probe_capabilities(port: u16) -> Result<GatewayCapabilities, String> {
+    let client = crate::build_http_client()?;
+    let (health, info, loglevel, completion, websocket) = tokio::join!(
+        endpoint_exists(&client, port, "/health"),
+        endpoint_exists(&client, port, "/info"),
+        endpoint_exists(&client, port, "/loglevel"),
+        endpoint_exists(&client, port, "/v1/completions"),
+        websocket_accepts(port),
+    );
+    Ok(GatewayCapabilities {
+        health,
+        info,
+        loglevel,
+        completion,
+        websocket,
+    })
+}
+
+/// Probes the running gateway's optional endpoints. Cached until the gateway
+/// is restarted.
+#[tauri::command]
+pub(crate) async fn probe_gateway_capabilities(
+    app: tauri::AppHandle,
+    state: State<'_, GatewayState>,
+    cache: State<'_, CapabilityCache>,
+) -> Result<GatewayCapabilities, AppError> {
+    let status = crate::gateway_status_internal(&app, &state).map_err(AppError::Gateway)?;
+    let pid = status
+        .pid
+        .ok_or_else(|| AppError::Gateway("Gateway is not running".to_string()))?;
+    if let Ok(cached) = cache.0.lock() {
+        if let Some((cached_pid, capabilities)) = cached.as_ref() {
+            if *cached_pid == pid {
+                return Ok(capabilities.clone());
+            }
+        }
+    }
+
+    let capabilities = probe_capabilities(status.port)
+        .await
+        .map_err(AppError::Gateway)?;
+    // A gateway that's still starting answers nothing; don't cache that.
+    if capabilities.health {
+        if let Ok(mut cached) = cache.0.lock() {
+            *cached = Some((pid, capabilities.clone()));
+        }
+    }
+    Ok(capabilities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_probe_capabilities_treats_404_as_missing() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                    let status = if request.starts_with("GET /health ") {
+                        "200 OK"
+                    } else if request.starts_with("GET /v1/completions ") {
+                        "405 Method Not Allowed"
+                    } else {
+                        "404 Not Found"
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                        status
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        let capabilities = probe_capabilities(port).await.unwrap();
+        assert_eq!(
+            capabilities,
+            GatewayCapabilities {
+                health: true,
+                completion: true,
+                ..Default::default()
+            }
+        );
+    }
+}
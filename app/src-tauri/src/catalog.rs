@@ -0,0 +1,220 @@
+//! The list of downloadable models (`fetch_model_catalog`), fetched from
+//! `model_catalog_url` (by default the `app/catalog.json` published with the
+//! project) so a new model doesn't need a new build.
+//!
+//! The last good copy is cached in `~/.moose/catalog.json` for offline use;
+//! without one, the built-in registry is returned.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+
+use crate::error::AppError;
+use crate::ModelSpec;
+
+const DEFAULT_CATALOG_URL: &str =
+    "https://raw.githubusercontent.com/OpenMoose/openmoose/main/app/catalog.json";
+const CATALOG_FILENAME: &str = "catalog.json";
+const CATALOG_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// One model in the catalog; the serializable form of `ModelSpec`.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub(crate) struct CatalogModel {
+    pub id: String,
+    pub filename: String,
+    /// Mirrors, in order of preference.
+    pub urls: Vec<String>,
+    /// Smallest size a complete download can have.
+    pub min_size: u64,
+    /// Lowercase hex, if the digest is pinned.
+    #[serde(default)]
+    pub sha256: Option<String>,
+    #[serde(default)]
+    pub gateway_env: BTreeMap<String, String>,
+}
+
+impl From<&ModelSpec> for CatalogModel {
+    fn from(model: &ModelSpec) -> Self {
+        CatalogModel {
+            id: model.id.to_string(),
+            filename: model.filename.to_string(),
+            urls: model.urls.iter().map(|url| url.to_string()).collect(),
+            min_size: model.min_size,
+            sha256: model.sha256.map(str::to_string),
+            gateway_env: model
+                .gateway_env
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Catalog {
+    models: Vec<CatalogModel>,
+}
+
+fn builtin_catalog() -> Vec<CatalogModel> {
+    crate::MODEL_REGISTRY
+        .iter()
+        .map(CatalogModel::from)
+        .collect()
+}
+
+fn catalog_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    Ok(crate::get_moose_dir(app)?.join(CATALOG_FILENAME))
+}
+
+fn catalog_url<R: Runtime>(app: &AppHandle<R>) -> String {
+    crate::get_config_internal(app)
+        .ok()
+        .and_then(|c| c.model_catalog_url)
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| DEFAULT_CATALOG_URL.to_string())
+}
+
+/// Parses a catalog document, rejecting it whole if any entry is unusable.
+fn parse_catalog(text: &str) -> Result<Vec<CatalogModel>, String> {
+    let catalog: Catalog =
+        serde_json::from_str(text).map_err(|e| format!("Invalid model catalog: {}", e))?;
+    if catalog.models.is_empty() {
+        return Err("Model catalog lists no models".to_string());
+    }
+    for model in &catalog.models {
+        validate_model(model).map_err(|e| format!("Model '{}' in catalog: {}", model.id, e))?;
+    }
+    Ok(catalog.models)
+}
+
+fn validate_model(model: &CatalogModel) -> Result<(), String> {
+    if model.id.trim().is_empty() {
+        return Err("missing id".to_string());
+    }
+    if !crate::is_plain_filename(&model.filename) {
+        return Err(format!(
+            "filename '{}' is not a plain file name",
+            model.filename
+        ));
+    }
+    if model.urls.is_empty() {
+        return Err("no download URLs".to_string());
+    }
+    for url in &model.urls {
+        crate::check_http_url("url", url)?;
+    }
+    if let Some(digest) = &model.sha256 {
+        if digest.len() != 64 || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("sha256 '{}' is not a SHA-256 hex digest", digest));
+        }
+    }
+    Ok(())
+}
+
+async fn download_catalog<R: Runtime>(app: &AppHandle<R>, url: &str) -> Result<String, String> {
+    let client = crate::download_client_builder(app)?
+        .timeout(CATALOG_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| crate::describe_request_error(&e))?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+    response
+        .text()
+        .await
+        .map_err(|e| crate::describe_request_error(&e))
+}
+
+/// Replaces the cached catalog via a temp file, so a crash can't leave a
+/// truncated one behind.
+fn write_cache(path: &Path, text: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let staging = path.with_extension("json.tmp");
+    let mut file = std::fs::File::create(&staging)?;
+    file.write_all(text.as_bytes())?;
+    file.sync_all()?;
+    std::fs::rename(&staging, path)
+}
+
+fn read_cache(path: &Path) -> Result<Vec<CatalogModel>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    parse_catalog(&text)
+}
+
+/// Models available to download, for the model picker: the remote catalog,
+/// or when that can't be fetched the cached copy, or the built-in list.
+#[tauri::command]
+pub(crate) async fn fetch_model_catalog(app: AppHandle) -> Result<Vec<CatalogModel>, AppError> {
+    let url = catalog_url(&app);
+    let cache = catalog_path(&app)?;
+    let fetched = download_catalog(&app, &url)
+        .await
+        .and_then(|text| parse_catalog(&text).map(|models| (text, models)));
+    match fetched {
+        Ok((text, models)) => {
+            if let Err(e) = write_cache(&cache, &text) {
+                tracing::warn!("Could not cache the model catalog: {}", e);
+            }
+            return Ok(models);
+        }
+        Err(e) => tracing::warn!("Could not fetch the model catalog from {}: {}", url, e),
+    }
+    match read_cache(&cache) {
+        Ok(models) => {
+            tracing::info!("Using the cached model catalog");
+            Ok(models)
+        }
+        Err(_) => {
+            tracing::info!("Using the built-in model catalog");
+            Ok(builtin_catalog())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_published_catalog_matches_builtin() {
+        let published = parse_catalog(include_str!("../../catalog.json")).unwrap();
+        assert_eq!(published, builtin_catalog());
+    }
+
+    #[test]
+    fn test_parse_catalog_rejects_bad_entries() {
+        let catalog = |model: serde_json::Value| {
+            parse_catalog(&serde_json::json!({ "models": [model] }).to_string())
+        };
+        let good = serde_json::json!({
+            "id": "tiny",
+            "filename": "tiny.gguf",
+            "urls": ["https://mirror.example/tiny.gguf"],
+            "min_size": 1024,
+        });
+        assert_eq!(catalog(good.clone()).unwrap()[0].id, "tiny");
+
+        let mut escape = good.clone();
+        escape["filename"] = "../tiny.gguf".into();
+        assert!(catalog(escape).is_err());
+        let mut no_urls = good.clone();
+        no_urls["urls"] = serde_json::json!([]);
+        assert!(catalog(no_urls).is_err());
+        let mut bad_digest = good;
+        bad_digest["sha256"] = "abc".into();
+        assert!(catalog(bad_digest).is_err());
+        assert!(parse_catalog(r#"{ "models": [] }"#).is_err());
+        assert!(parse_catalog("<html>").is_err());
+    }
+}
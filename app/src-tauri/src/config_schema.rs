@@ -0,0 +1,409 @@
+//! Self-description of the config.json fields the app owns
+//! (`describe_config_schema`), so the settings UI can render and validate a
+//! form without hard-coding each field.
+//!
+//! `CONFIG_FIELDS` must list exactly the fields of `AppConfig`; a test keeps
+//! the two in step. `update_config` enforces the same limits through
+//! `check_constraints`, so the form and the backend can't disagree.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub(crate) struct FieldConstraints {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub one_of: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct ConfigFieldInfo {
+    pub key: String,
+    /// JSON type: "boolean", "integer", "string" or "object", with "?" when
+    /// the field may be null (meaning "use the default").
+    #[serde(rename = "type")]
+    pub field_type: String,
+    pub default: serde_json::Value,
+    pub description: String,
+    pub constraints: FieldConstraints,
+}
+
+struct ConfigField {
+    key: &'static str,
+    field_type: &'static str,
+    /// Default as JSON text.
+    default: &'static str,
+    description: &'static str,
+    min: Option<u64>,
+    max: Option<u64>,
+    one_of: &'static [&'static str],
+}
+
+const CONFIG_FIELDS: &[ConfigField] = &[
+    ConfigField {
+        key: "schema_version",
+        field_type: "integer",
+        default: "1",
+        description: "Layout version of config.json. Maintained by the app; don't edit it.",
+        min: None,
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "setup_complete",
+        field_type: "boolean",
+        default: "false",
+        description: "Whether onboarding has finished. Set to false to re-run the setup wizard.",
+        min: None,
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "theme",
+        field_type: "string",
+        default: "\"dark\"",
+        description: "UI theme. \"system\" follows the OS light/dark appearance.",
+        min: None,
+        max: None,
+        one_of: &["dark", "light", "system"],
+    },
+    ConfigField {
+        key: "node_check_timeout_secs",
+        field_type: "integer?",
+        default: "10",
+        description: "How long the Node.js environment check may run before timing out.",
+        min: Some(1),
+        max: Some(600),
+        one_of: &[],
+    },
+    ConfigField {
+        key: "docker_check_timeout_secs",
+        field_type: "integer?",
+        default: "30",
+        description: "How long the Docker environment check may run before timing out.",
+        min: Some(1),
+        max: Some(600),
+        one_of: &[],
+    },
+    ConfigField {
+        key: "restart_hung_gateway",
+        field_type: "boolean?",
+        default: "false",
+        description: "Force-restart the gateway when it stops answering health probes.",
+        min: None,
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "download_trace",
+        field_type: "boolean?",
+        default: "false",
+        description: "Log per-second download throughput to ~/.moose/logs/download-trace.csv.",
+        min: None,
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "require_docker",
+        field_type: "boolean?",
+        default: "false",
+        description: "Wait for the Docker daemon before auto-starting the gateway.",
+        min: None,
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "verify_resume_overlap",
+        field_type: "boolean?",
+        default: "false",
+        description: "Re-download the last 1 MB of a partial model and compare it before resuming.",
+        min: None,
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "download_max_bytes_per_sec",
+        field_type: "integer?",
+        default: "null",
+        description: "Cap on model download speed in bytes per second. Unset or 0 is unlimited.",
+        min: Some(0),
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "download_max_connections",
+        field_type: "integer?",
+        default: "4",
+        description: "Parallel connections per model download. 1 downloads over a single stream.",
+        min: Some(1),
+        max: Some(16),
+        one_of: &[],
+    },
+    ConfigField {
+        key: "download_max_attempts",
+        field_type: "integer?",
+        default: "5",
+        description:
+            "Reconnect attempts, with growing delays, before a failed model download gives up.",
+        min: Some(1),
+        max: Some(20),
+        one_of: &[],
+    },
+    ConfigField {
+        key: "progress_interval_ms",
+        field_type: "integer?",
+        default: "200",
+        description: "Minimum milliseconds between download progress updates. Parallel downloads report every 500 ms regardless.",
+        min: Some(10),
+        max: Some(10000),
+        one_of: &[],
+    },
+    ConfigField {
+        key: "progress_interval_bytes",
+        field_type: "integer?",
+        default: "null",
+        description: "Also update download progress once this many bytes arrived since the last update, if that comes first. Unset or 0 uses the interval alone.",
+        min: Some(0),
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "node_path",
+        field_type: "string?",
+        default: "null",
+        description:
+            "Node.js binary for the gateway. Unset uses the bundled runtime, then the system one.",
+        min: None,
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "selected_model",
+        field_type: "string?",
+        default: "null",
+        description: "Registry id of the model to download and run. Unset uses the default model.",
+        min: None,
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "model_url",
+        field_type: "string?",
+        default: "null",
+        description: "http(s) URL to download the active model from instead of its usual source.",
+        min: None,
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "custom_model_filename",
+        field_type: "string?",
+        default: "null",
+        description:
+            "File name to store and run the active model under, e.g. for a sideloaded GGUF.",
+        min: None,
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "model_catalog_url",
+        field_type: "string?",
+        default: "null",
+        description: "http(s) URL of the model catalog. Unset uses the project's catalog.",
+        min: None,
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "proxy_url",
+        field_type: "string?",
+        default: "null",
+        description: "http(s) proxy for model downloads. Unset uses HTTP_PROXY/HTTPS_PROXY; NO_PROXY applies either way.",
+        min: None,
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "hf_token",
+        field_type: "string?",
+        default: "null",
+        description: "Hugging Face access token for gated or private models, sent only to Hugging Face. Unset uses HF_TOKEN.",
+        min: None,
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "gateway_port",
+        field_type: "integer?",
+        default: "18789",
+        description: "Port the gateway listens on. Overrides the GATEWAY_PORT env var; an active profile's port overrides this.",
+        min: Some(1),
+        max: Some(65535),
+        one_of: &[],
+    },
+    ConfigField {
+        key: "data_dir",
+        field_type: "string?",
+        default: "null",
+        description: "Absolute path for models, logs and caches instead of the default data directory (~/.moose, or ~/.local/share/moose on Linux). Existing files are not moved; the MOOSE_HOME env var overrides this.",
+        min: None,
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "telemetry_enabled",
+        field_type: "boolean",
+        default: "false",
+        description: "Send anonymous usage events (no paths or personal data) to telemetry_endpoint.",
+        min: None,
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "telemetry_endpoint",
+        field_type: "string?",
+        default: "null",
+        description: "http(s) URL usage events are posted to. Nothing is sent without one.",
+        min: None,
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "profiles",
+        field_type: "object",
+        default: "{}",
+        description:
+            "Named gateway setups. Edited through the profile commands, not update_config.",
+        min: None,
+        max: None,
+        one_of: &[],
+    },
+    ConfigField {
+        key: "active_profile",
+        field_type: "string?",
+        default: "null",
+        description: "Profile the gateway runs with. Unset means the top-level config as-is.",
+        min: None,
+        max: None,
+        one_of: &[],
+    },
+];
+
+/// Keys of every config field the app owns; anything else in config.json
+/// belongs to the gateway or the user.
+pub(crate) fn app_owned_keys() -> impl Iterator<Item = &'static str> {
+    CONFIG_FIELDS.iter().map(|field| field.key)
+}
+
+/// Checks every set field of `config` (an `AppConfig` as JSON) against its
+/// `min`, `max` and `one_of`. Unset (null) fields are not checked.
+pub(crate) fn check_constraints(config: &serde_json::Value) -> Result<(), String> {
+    for field in CONFIG_FIELDS {
+        let Some(value) = config.get(field.key).filter(|v| !v.is_null()) else {
+            continue;
+        };
+        if let Some(number) = value.as_u64() {
+            let too_low = field.min.is_some_and(|min| number < min);
+            let too_high = field.max.is_some_and(|max| number > max);
+            if too_low || too_high {
+                let range = match (field.min, field.max) {
+                    (Some(min), Some(max)) => format!("between {} and {}", min, max),
+                    (Some(min), None) => format!("at least {}", min),
+                    (None, Some(max)) => format!("at most {}", max),
+                    (None, None) => unreachable!(),
+                };
+                return Err(format!("Invalid {}: must be {}", field.key, range));
+            }
+        }
+        if let Some(text) = value.as_str() {
+            if !field.one_of.is_empty() && !field.one_of.contains(&text) {
+                return Err(format!(
+                    "Invalid {} '{}': must be one of {}",
+                    field.key,
+                    text,
+                    field.one_of.join(", ")
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Every config field the app owns, with its type, default and limits.
+#[tauri::command]
+pub(crate) async fn describe_config_schema() -> Result<Vec<ConfigFieldInfo>, AppError> {
+    CONFIG_FIELDS
+        .iter()
+        .map(|field| {
+            Ok(ConfigFieldInfo {
+                key: field.key.to_string(),
+                field_type: field.field_type.to_string(),
+                default: serde_json::from_str(field.default).map_err(|e| {
+                    AppError::Other(format!("Bad default for {}: {}", field.key, e))
+                })?,
+                description: field.description.to_string(),
+                constraints: FieldConstraints {
+                    min: field.min,
+                    max: field.max,
+                    one_of: field.one_of.iter().map(|s| s.to_string()).collect(),
+                },
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_schema_matches_app_config() {
+        let mut config = crate::AppConfig::default();
+        // Skipped when empty; give it an entry so the key shows up.
+        config
+            .profiles
+            .insert("test".to_string(), Default::default());
+        let serde_json::Value::Object(fields) = serde_json::to_value(&config).unwrap() else {
+            panic!("AppConfig should serialize to an object");
+        };
+        let mut config_keys: Vec<&str> = fields.keys().map(String::as_str).collect();
+        let mut schema_keys: Vec<&str> = CONFIG_FIELDS.iter().map(|f| f.key).collect();
+        config_keys.sort_unstable();
+        schema_keys.sort_unstable();
+        assert_eq!(config_keys, schema_keys);
+
+        // Every default must at least be valid JSON.
+        assert_eq!(
+            describe_config_schema().await.unwrap().len(),
+            CONFIG_FIELDS.len()
+        );
+    }
+
+    #[test]
+    fn test_check_constraints() {
+        // Through serde, so fields get their serde defaults (the theme).
+        let defaults: crate::AppConfig = serde_json::from_value(crate::empty_config()).unwrap();
+        let config = serde_json::to_value(defaults).unwrap();
+        assert!(check_constraints(&config).is_ok());
+
+        let with = |key: &str, value: serde_json::Value| {
+            let mut config = config.clone();
+            config[key] = value;
+            check_constraints(&config)
+        };
+        assert_eq!(
+            with("progress_interval_ms", 0.into()).unwrap_err(),
+            "Invalid progress_interval_ms: must be between 10 and 10000"
+        );
+        assert!(with("node_check_timeout_secs", 0.into()).is_err());
+        assert!(with("download_max_attempts", 21.into()).is_err());
+        assert!(with("gateway_port", 0.into()).is_err());
+        assert!(with("download_max_bytes_per_sec", 0.into()).is_ok());
+        assert!(with("theme", "sepia".into()).is_err());
+        assert!(with("theme", "system".into()).is_ok());
+    }
+}
@@ -0,0 +1,144 @@
+//! Watches config.json for changes made outside the app (the gateway, or a
+//! user with an editor) and emits `config-changed` with the fresh `AppConfig`.
+//!
+//! The directory is watched rather than the file, since `write_config_file`
+//! replaces config.json by rename. A burst of events is reported once, after
+//! `DEBOUNCE` of quiet, and content the app wrote itself is not reported.
+
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::path::Path;
+use std::sync::mpsc::Receiver;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::AppConfig;
+
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// The config.json content the app last wrote or reported.
+#[derive(Default)]
+pub(crate) struct ConfigWatch(Mutex<Option<serde_json::Value>>);
+
+impl ConfigWatch {
+    /// Called after every write by the app, so the watcher skips it.
+    pub(crate) fn remember(&self, config: &serde_json::Value) {
+        if let Ok(mut last) = self.0.lock() {
+            *last = Some(config.clone());
+        }
+    }
+
+    /// Records `config` and reports whether it differs from the last one seen.
+    fn is_new(&self, config: &serde_json::Value) -> bool {
+        let Ok(mut last) = self.0.lock() else {
+            return false;
+        };
+        if last.as_ref() == Some(config) {
+            return false;
+        }
+        *last = Some(config.clone());
+        true
+    }
+}
+
+/// Starts watching config.json. Watching is best effort: if it can't be set
+/// up, the app works as before and only picks up changes on the next read.
+pub(crate) fn spawn(app: AppHandle) {
+    let path = match crate::get_config_path(&app) {
+        Ok(path) => path,
+        Err(e) => {
+            tracing::warn!("Config watcher disabled: {}", e);
+            return;
+        }
+    };
+    let Some(dir) = path.parent().map(Path::to_path_buf) else {
+        return;
+    };
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        tracing::warn!("Config watcher disabled: {}", e);
+        return;
+    }
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let watched = path.clone();
+    let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event {
+            if touches(&event, &watched) {
+                let _ = tx.send(());
+            }
+        }
+    });
+    let mut watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            tracing::warn!("Config watcher disabled: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+        tracing::warn!("Config watcher disabled: {}", e);
+        return;
+    }
+
+    std::thread::spawn(move || {
+        // Dropping the watcher stops it, so it lives as long as this thread.
+        let _watcher = watcher;
+        while rx.recv().is_ok() {
+            wait_for_quiet(&rx, DEBOUNCE);
+            report_change(&app, &path);
+        }
+    });
+}
+
+/// Whether `event` changed config.json itself (not the .bak or temp file).
+fn touches(event: &notify::Event, config_path: &Path) -> bool {
+    !matches!(event.kind, EventKind::Access(_))
+        && event.paths.iter().any(|path| path == config_path)
+}
+
+/// Swallows further events until none has arrived for `quiet`.
+fn wait_for_quiet(rx: &Receiver<()>, quiet: Duration) {
+    while rx.recv_timeout(quiet).is_ok() {}
+}
+
+fn report_change(app: &AppHandle, path: &Path) {
+    // Half-saved or deleted: wait for the next event rather than report it.
+    let Some(raw) = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+    else {
+        return;
+    };
+    if !app.state::<ConfigWatch>().is_new(&raw) {
+        return;
+    }
+    match serde_json::from_value::<AppConfig>(crate::migrate_config(raw)) {
+        Ok(config) => {
+            tracing::info!("config.json changed on disk");
+            let _ = app.emit("config-changed", config);
+        }
+        Err(e) => tracing::warn!("Ignoring changed config.json: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burst_of_events_is_reported_once() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        for _ in 0..3 {
+            tx.send(()).unwrap();
+        }
+        assert!(rx.recv().is_ok());
+        wait_for_quiet(&rx, Duration::from_millis(50));
+        assert!(rx.try_recv().is_err());
+
+        let watch = ConfigWatch::default();
+        let config = serde_json::json!({ "theme": "light" });
+        watch.remember(&config);
+        assert!(!watch.is_new(&config));
+        assert!(watch.is_new(&serde_json::json!({ "theme": "dark" })));
+    }
+}
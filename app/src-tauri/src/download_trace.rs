@@ -0,0 +1,177 @@
+//! Opt-in per-second throughput log for model downloads (`download_trace` in
+//! config.json), for diagnosing flaky networks after the fact.
+//!
+//! Every download session starts with a fresh CSV header, so the rows after
+//! the last header are the most recent session.
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Runtime};
+
+use crate::error::AppError;
+
+const TRACE_FILENAME: &str = "download-trace.csv";
+const TRACE_HEADER: &str = "timestamp,downloaded,bytes_per_sec";
+const TRACE_INTERVAL: Duration = Duration::from_secs(1);
+/// Past this size the trace is rotated to `download-trace.csv.1` when the next
+/// session starts.
+const TRACE_MAX_BYTES: u64 = 5 * 1024 * 1024;
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub(crate) struct TraceRow {
+    /// Unix time in milliseconds.
+    pub timestamp: u64,
+    pub downloaded: u64,
+    pub bytes_per_sec: u64,
+}
+
+fn trace_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    Ok(crate::get_moose_dir(app)?.join("logs").join(TRACE_FILENAME))
+}
+
+fn now_epoch_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// An open trace for one download session.
+pub(crate) struct DownloadTrace {
+    file: File,
+    last_at: Instant,
+    last_downloaded: u64,
+}
+
+impl DownloadTrace {
+    /// Opens the trace if `download_trace` is enabled. Tracing is best effort:
+    /// any I/O problem just disables it for this download.
+    pub(crate) fn start_if_enabled<R: Runtime>(
+        app: &AppHandle<R>,
+        downloaded: u64,
+    ) -> Option<Self> {
+        let enabled = crate::get_config_internal(app)
+            .ok()
+            .and_then(|c| c.download_trace)
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+        let path = trace_path(app).ok()?;
+        match Self::open(path, downloaded) {
+            Ok(trace) => Some(trace),
+            Err(e) => {
+                tracing::warn!("Download trace disabled: {}", e);
+                None
+            }
+        }
+    }
+
+    fn open(path: PathBuf, downloaded: u64) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        if path.metadata().map(|m| m.len()).unwrap_or(0) > TRACE_MAX_BYTES {
+            std::fs::rename(&path, path.with_extension("csv.1"))?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        writeln!(file, "{}", TRACE_HEADER)?;
+        Ok(DownloadTrace {
+            file,
+            last_at: Instant::now(),
+            last_downloaded: downloaded,
+        })
+    }
+
+    /// Appends a row if at least `TRACE_INTERVAL` has passed since the last one.
+    pub(crate) fn record(&mut self, downloaded: u64) {
+        let elapsed = self.last_at.elapsed();
+        if elapsed < TRACE_INTERVAL {
+            return;
+        }
+        let bytes = downloaded.saturating_sub(self.last_downloaded);
+        let bytes_per_sec = (bytes as f64 / elapsed.as_secs_f64()) as u64;
+        let _ = writeln!(
+            self.file,
+            "{},{},{}",
+            now_epoch_millis(),
+            downloaded,
+            bytes_per_sec
+        );
+        self.last_at = Instant::now();
+        self.last_downloaded = downloaded;
+    }
+}
+
+/// Parses the rows written after the last session header, skipping any line
+/// that doesn't parse (e.g. one cut short by a crash).
+fn parse_last_session(content: &str) -> Vec<TraceRow> {
+    let session = content
+        .rsplit_once(TRACE_HEADER)
+        .map(|(_, rows)| rows)
+        .unwrap_or("");
+    session
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.trim().split(',').map(|f| f.parse::<u64>());
+            match (fields.next(), fields.next(), fields.next(), fields.next()) {
+                (Some(Ok(timestamp)), Some(Ok(downloaded)), Some(Ok(bytes_per_sec)), None) => {
+                    Some(TraceRow {
+                        timestamp,
+                        downloaded,
+                        bytes_per_sec,
+                    })
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Throughput rows from the most recent traced download, oldest first.
+#[tauri::command]
+pub(crate) async fn get_download_trace(app: tauri::AppHandle) -> Result<Vec<TraceRow>, AppError> {
+    let path = trace_path(&app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    Ok(parse_last_session(&content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_last_session_only() {
+        let content = "timestamp,downloaded,bytes_per_sec\n\
+                       1000,10,10\n\
+                       timestamp,downloaded,bytes_per_sec\n\
+                       2000,50,40\n\
+                       3000,90,40\n\
+                       4000,9";
+        assert_eq!(
+            parse_last_session(content),
+            vec![
+                TraceRow {
+                    timestamp: 2000,
+                    downloaded: 50,
+                    bytes_per_sec: 40
+                },
+                TraceRow {
+                    timestamp: 3000,
+                    downloaded: 90,
+                    bytes_per_sec: 40
+                },
+            ]
+        );
+        assert!(parse_last_session("").is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+//! `AppError`, what commands return to the frontend instead of a bare string.
+//!
+//! Serialized as `{ kind, message }` so the UI can branch on `kind` rather
+//! than match message text. Internal helpers still fail with `String`;
+//! commands pick the kind at the boundary (`.map_err(AppError::Gateway)`),
+//! and anything left unclassified comes through `?` as `Other`.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub(crate) enum AppError {
+    /// Reading or writing local files.
+    Io(String),
+    /// Talking to a remote host (model mirrors, proxies).
+    Network(String),
+    /// config.json is unreadable, or a setting is invalid.
+    Config(String),
+    /// Starting, stopping or reaching the gateway.
+    Gateway(String),
+    /// The model download itself (integrity, disk space, a stalled stream).
+    Download(String),
+    /// A download response came back as an HTML or JSON page (an expired
+    /// signed URL, rate limiting) rather than the model file.
+    WrongContentType(String),
+    /// A download response's Content-Length doesn't match the size probed
+    /// for the model, so it's likely an error page too.
+    WrongContentLength(String),
+    NotFound(String),
+    /// A required tool or service is present but not usable (e.g. the Docker
+    /// daemon isn't running).
+    Unavailable(String),
+    /// Refused because something else is in progress (e.g. a download).
+    Busy(String),
+    TimedOut(String),
+    Cancelled(String),
+    /// Stopped by the user, to be picked up again later (a paused download).
+    Paused(String),
+    Other(String),
+}
+
+impl AppError {
+    pub(crate) fn message(&self) -> &str {
+        match self {
+            AppError::Io(message)
+            | AppError::Network(message)
+            | AppError::Config(message)
+            | AppError::Gateway(message)
+            | AppError::Download(message)
+            | AppError::WrongContentType(message)
+            | AppError::WrongContentLength(message)
+            | AppError::NotFound(message)
+            | AppError::Unavailable(message)
+            | AppError::Busy(message)
+            | AppError::TimedOut(message)
+            | AppError::Cancelled(message)
+            | AppError::Paused(message)
+            | AppError::Other(message) => message,
+        }
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Other(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Other(message.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::NotFound => AppError::NotFound(e.to_string()),
+            std::io::ErrorKind::TimedOut => AppError::TimedOut(e.to_string()),
+            _ => AppError::Io(e.to_string()),
+        }
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            AppError::TimedOut(crate::describe_request_error(&e))
+        } else {
+            AppError::Network(crate::describe_request_error(&e))
+        }
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        AppError::Config(e.to_string())
+    }
+}
+
+impl From<tauri::Error> for AppError {
+    fn from(e: tauri::Error) -> Self {
+        AppError::Other(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_error_shape_and_conversions() {
+        let err = AppError::Gateway("Gateway is not running".to_string());
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            serde_json::json!({ "kind": "gateway", "message": "Gateway is not running" })
+        );
+        assert_eq!(err.to_string(), "Gateway is not running");
+        assert_eq!(
+            serde_json::to_value(AppError::Paused("Download paused".to_string())).unwrap(),
+            serde_json::json!({ "kind": "paused", "message": "Download paused" })
+        );
+
+        let missing = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        assert_eq!(
+            AppError::from(missing),
+            AppError::NotFound("no such file".to_string())
+        );
+        assert_eq!(
+            AppError::from("plain".to_string()),
+            AppError::Other("plain".to_string())
+        );
+    }
+}
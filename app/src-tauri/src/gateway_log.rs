@@ -0,0 +1,167 @@
+//! The gateway's stdout/stderr persisted to `~/.moose/logs/gateway.log`, so it
+//! survives app restarts for support bundles and post-mortems
+//! (`get_gateway_log_path`).
+//!
+//! The file is rotated once it passes `LOG_MAX_BYTES`: `gateway.log.1` is the
+//! most recent old file, and only `LOG_KEEP` of those are kept.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tauri::{AppHandle, Runtime};
+
+use crate::error::AppError;
+
+const LOG_FILENAME: &str = "gateway.log";
+const LOG_MAX_BYTES: u64 = 5 * 1024 * 1024;
+const LOG_KEEP: usize = 3;
+
+pub(crate) fn log_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    Ok(crate::get_moose_dir(app)?.join("logs").join(LOG_FILENAME))
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut rotated = path.as_os_str().to_os_string();
+    rotated.push(format!(".{}", index));
+    PathBuf::from(rotated)
+}
+
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    len: u64,
+    max_bytes: u64,
+}
+
+impl LogFile {
+    fn open(path: PathBuf, max_bytes: u64) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        let len = file.metadata()?.len();
+        Ok(LogFile {
+            path,
+            file,
+            len,
+            max_bytes,
+        })
+    }
+
+    /// Shifts `gateway.log.N` up by one, dropping the oldest, and starts a
+    /// fresh `gateway.log`.
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+        let _ = std::fs::remove_file(rotated_path(&self.path, LOG_KEEP));
+        for index in (1..LOG_KEEP).rev() {
+            let from = rotated_path(&self.path, index);
+            if from.exists() {
+                std::fs::rename(&from, rotated_path(&self.path, index + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        *self = LogFile::open(self.path.clone(), self.max_bytes)?;
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        let bytes = line.len() as u64 + 1;
+        if self.len > 0 && self.len + bytes > self.max_bytes {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.file.flush()?;
+        self.len += bytes;
+        Ok(())
+    }
+}
+
+/// The open log, shared by the gateway's reader threads. Writes and rotation
+/// both happen under the one lock, so they can't race each other.
+#[derive(Default)]
+pub(crate) struct GatewayLogFile(Mutex<Option<LogFile>>);
+
+impl GatewayLogFile {
+    /// Opens the log for a newly spawned gateway and marks where its output
+    /// starts. Logging is best effort: if the file can't be opened, the
+    /// output still reaches the UI.
+    pub(crate) fn open<R: Runtime>(&self, app: &AppHandle<R>, pid: u32) {
+        let opened = log_path(app)
+            .and_then(|path| LogFile::open(path, LOG_MAX_BYTES).map_err(|e| e.to_string()));
+        let Ok(mut current) = self.0.lock() else {
+            return;
+        };
+        *current = match opened {
+            Ok(log) => Some(log),
+            Err(e) => {
+                tracing::warn!("Gateway log disabled: {}", e);
+                None
+            }
+        };
+        drop(current);
+        self.write("app", &format!("=== gateway started (pid {}) ===", pid));
+    }
+
+    pub(crate) fn write(&self, stream: &str, line: &str) {
+        let Ok(mut current) = self.0.lock() else {
+            return;
+        };
+        let Some(log) = current.as_mut() else {
+            return;
+        };
+        let stamped = format!(
+            "{} [{}] {}",
+            httpdate::fmt_http_date(SystemTime::now()),
+            stream,
+            line
+        );
+        if let Err(e) = log.write_line(&stamped) {
+            tracing::warn!("Gateway log disabled: {}", e);
+            *current = None;
+        }
+    }
+
+    /// Flushes and closes the log once the gateway has stopped.
+    pub(crate) fn close(&self) {
+        if let Ok(mut current) = self.0.lock() {
+            if let Some(mut log) = current.take() {
+                let _ = log.file.flush();
+            }
+        }
+    }
+}
+
+/// Where the gateway log is written, for an "Open log" action. The file may
+/// not exist yet if the gateway has never run.
+#[tauri::command]
+pub(crate) async fn get_gateway_log_path(app: tauri::AppHandle) -> Result<String, AppError> {
+    Ok(log_path(&app)?.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_rotation_keeps_newest_files() {
+        let dir = std::env::temp_dir().join("openmoose-gateway-log-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join(LOG_FILENAME);
+        let mut log = LogFile::open(path.clone(), 10).unwrap();
+        for line in ["first", "second", "third", "fourth", "fifth"] {
+            log.write_line(line).unwrap();
+        }
+
+        let read = |p: &Path| std::fs::read_to_string(p).unwrap();
+        assert_eq!(read(&path), "fifth\n");
+        assert_eq!(read(&rotated_path(&path, 1)), "fourth\n");
+        assert_eq!(read(&rotated_path(&path, 3)), "second\n");
+        assert!(!rotated_path(&path, LOG_KEEP + 1).exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
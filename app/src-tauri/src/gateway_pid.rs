@@ -0,0 +1,131 @@
+//! `~/.moose/gateway.pid`: the PID of the gateway the app spawned. If the app
+//! dies without running its exit handler, the gateway keeps running; the next
+//! launch finds it through this file and stops it (`reclaim_orphan`) instead
+//! of starting a second one that fails on the port.
+//!
+//! The recorded PID is often a pnpm/npm wrapper, while the port belongs to the
+//! Node server it forked, so ownership is checked down the process tree.
+
+use std::path::{Path, PathBuf};
+use sysinfo::{Pid, ProcessRefreshKind, RefreshKind, System};
+use tauri::{AppHandle, Runtime};
+
+const PID_FILENAME: &str = "gateway.pid";
+/// Parent links followed from the port owner looking for the recorded PID.
+const MAX_TREE_DEPTH: usize = 16;
+
+fn pid_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    Ok(crate::get_moose_dir(app)?.join(PID_FILENAME))
+}
+
+fn read_pid(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Records a freshly spawned gateway. Best effort: without the file, only the
+/// orphan cleanup on the next launch is lost.
+pub(crate) fn record<R: Runtime>(app: &AppHandle<R>, pid: u32) {
+    let written = pid_path(app)
+        .and_then(|path| std::fs::write(&path, format!("{}\n", pid)).map_err(|e| e.to_string()));
+    if let Err(e) = written {
+        tracing::warn!("Could not write gateway.pid: {}", e);
+    }
+}
+
+/// Forgets the gateway, once it has been stopped or has exited.
+pub(crate) fn clear<R: Runtime>(app: &AppHandle<R>) {
+    if let Ok(path) = pid_path(app) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Whether `pid` is `ancestor` itself or one of its descendants.
+fn descends_from(system: &System, pid: Pid, ancestor: Pid) -> bool {
+    let mut current = pid;
+    for _ in 0..MAX_TREE_DEPTH {
+        if current == ancestor {
+            return true;
+        }
+        match system.process(current).and_then(|p| p.parent()) {
+            Some(parent) => current = parent,
+            None => return false,
+        }
+    }
+    false
+}
+
+/// Called at startup: if gateway.pid names a live process that (through its
+/// children) still listens on `port`, it's a gateway orphaned by a previous
+/// run, so kill it and wait briefly for the port. A PID that has exited or
+/// now belongs to something else is left alone. The file is cleared either way.
+pub(crate) fn reclaim_orphan<R: Runtime>(app: &AppHandle<R>, port: u16) {
+    let Some(recorded) = pid_path(app).ok().and_then(|path| read_pid(&path)) else {
+        return;
+    };
+    clear(app);
+    let system = System::new_with_specifics(
+        RefreshKind::nothing().with_processes(ProcessRefreshKind::nothing()),
+    );
+    let recorded = Pid::from_u32(recorded);
+    let Some(wrapper) = system.process(recorded) else {
+        return;
+    };
+    let owner = crate::port_owner_pid(port)
+        .map(Pid::from_u32)
+        .filter(|&owner| descends_from(&system, owner, recorded));
+    let Some(owner) = owner else {
+        tracing::info!(
+            "Process {} from gateway.pid doesn't hold port {}, leaving it alone",
+            recorded,
+            port
+        );
+        return;
+    };
+
+    tracing::warn!(
+        "Stopping gateway left running by a previous launch (pid {}, port {})",
+        recorded,
+        port
+    );
+    if !crate::kill_gateway_tree(recorded.as_u32()) {
+        wrapper.kill();
+    }
+    if let Some(server) = system.process(owner) {
+        server.kill();
+    }
+    let deadline = std::time::Instant::now() + crate::PORT_RELEASE_TIMEOUT;
+    while !crate::probe_port(port) && std::time::Instant::now() < deadline {
+        std::thread::sleep(crate::PORT_RELEASE_POLL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_descends_from() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("5")
+            .spawn()
+            .unwrap();
+        let system = System::new_with_specifics(
+            RefreshKind::nothing().with_processes(ProcessRefreshKind::nothing()),
+        );
+        let me = Pid::from_u32(std::process::id());
+        let child_pid = Pid::from_u32(child.id());
+        assert!(descends_from(&system, child_pid, me));
+        assert!(descends_from(&system, me, me));
+        assert!(!descends_from(&system, me, child_pid));
+        let _ = child.kill();
+        let _ = child.wait();
+
+        let path = std::env::temp_dir().join("openmoose-gateway-pid-test");
+        std::fs::write(&path, "4242\n").unwrap();
+        assert_eq!(read_pid(&path), Some(4242));
+        std::fs::write(&path, "not a pid").unwrap();
+        assert_eq!(read_pid(&path), None);
+        let _ = std::fs::remove_file(&path);
+    }
+}
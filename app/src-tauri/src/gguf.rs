@@ -0,0 +1,357 @@
+//! Reads a model's GGUF header (`get_model_info`) for the "Model details"
+//! panel: architecture, context length, quantization and parameter count.
+//!
+//! Only the header is read, never the tensor data, and no more than
+//! `MAX_HEADER_BYTES` of it, so a corrupt length can't send the parser
+//! through the whole multi-GB file.
+
+use serde::{Deserialize, Serialize};
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use crate::error::AppError;
+
+pub(crate) const GGUF_MAGIC: &[u8; 4] = b"GGUF";
+/// The key/value section holds the tokenizer vocabulary, so it runs to a few
+/// MB; anything past this is not a header.
+const MAX_HEADER_BYTES: u64 = 64 * 1024 * 1024;
+const MAX_STRING_LEN: u64 = 1024 * 1024;
+/// Arrays of arrays go no deeper than this in real models; a corrupt header
+/// could otherwise nest deep enough to overflow the stack.
+const MAX_ARRAY_DEPTH: u32 = 8;
+
+// Metadata value types, as numbered by the GGUF spec.
+const TYPE_U8: u32 = 0;
+const TYPE_I8: u32 = 1;
+const TYPE_U16: u32 = 2;
+const TYPE_I16: u32 = 3;
+const TYPE_U32: u32 = 4;
+const TYPE_I32: u32 = 5;
+const TYPE_F32: u32 = 6;
+const TYPE_BOOL: u32 = 7;
+const TYPE_STRING: u32 = 8;
+const TYPE_ARRAY: u32 = 9;
+const TYPE_U64: u32 = 10;
+const TYPE_I64: u32 = 11;
+const TYPE_F64: u32 = 12;
+
+/// Returned by `get_model_info`. Fields the file doesn't declare are `None`.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub(crate) struct ModelInfo {
+    /// GGUF format version.
+    pub version: u32,
+    pub name: Option<String>,
+    /// e.g. "llama", "mistral3".
+    pub architecture: Option<String>,
+    /// Context window the model was trained with.
+    pub context_length: Option<u64>,
+    /// e.g. "Q4_K_M", from `general.file_type`.
+    pub quantization: Option<String>,
+    /// Summed from the tensor shapes.
+    pub parameter_count: u64,
+    pub tensor_count: u64,
+}
+
+/// A metadata value, kept only for the scalar types the panel shows.
+enum Value {
+    Int(u64),
+    Text(String),
+    Other,
+}
+
+struct Reader<R: Read> {
+    inner: R,
+}
+
+impl<R: Read> Reader<R> {
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut buf = [0u8; N];
+        self.inner.read_exact(&mut buf).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                "GGUF header is truncated: the download may be incomplete".to_string()
+            } else {
+                format!("Failed to read the model: {}", e)
+            }
+        })?;
+        Ok(buf)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        self.bytes().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        self.bytes().map(u64::from_le_bytes)
+    }
+
+    fn skip(&mut self, len: u64) -> Result<(), String> {
+        let skipped = std::io::copy(&mut (&mut self.inner).take(len), &mut std::io::sink())
+            .map_err(|e| format!("Failed to read the model: {}", e))?;
+        if skipped < len {
+            return Err("GGUF header is truncated: the download may be incomplete".to_string());
+        }
+        Ok(())
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let len = self.u64()?;
+        if len > MAX_STRING_LEN {
+            return Err(format!("Corrupt GGUF header: {} byte string", len));
+        }
+        let mut buf = vec![0u8; len as usize];
+        self.inner
+            .read_exact(&mut buf)
+            .map_err(|_| "GGUF header is truncated: the download may be incomplete".to_string())?;
+        Ok(String::from_utf8_lossy(&buf).into_owned())
+    }
+
+    /// Reads one value; `depth` counts the arrays it's nested in.
+    fn value(&mut self, value_type: u32, depth: u32) -> Result<Value, String> {
+        Ok(match value_type {
+            TYPE_U8 | TYPE_I8 | TYPE_BOOL => Value::Int(self.bytes::<1>()?[0] as u64),
+            TYPE_U16 | TYPE_I16 => Value::Int(u16::from_le_bytes(self.bytes()?) as u64),
+            TYPE_U32 | TYPE_I32 => Value::Int(self.u32()? as u64),
+            TYPE_U64 | TYPE_I64 => Value::Int(self.u64()?),
+            TYPE_F32 => {
+                self.skip(4)?;
+                Value::Other
+            }
+            TYPE_F64 => {
+                self.skip(8)?;
+                Value::Other
+            }
+            TYPE_STRING => Value::Text(self.string()?),
+            TYPE_ARRAY => {
+                if depth >= MAX_ARRAY_DEPTH {
+                    return Err(format!(
+                        "Corrupt GGUF header: arrays nested more than {} deep",
+                        MAX_ARRAY_DEPTH
+                    ));
+                }
+                let item_type = self.u32()?;
+                let len = self.u64()?;
+                match fixed_size(item_type) {
+                    Some(size) => self.skip(len.saturating_mul(size))?,
+                    None => {
+                        for _ in 0..len {
+                            self.value(item_type, depth + 1)?;
+                        }
+                    }
+                }
+                Value::Other
+            }
+            other => return Err(format!("Corrupt GGUF header: unknown value type {}", other)),
+        })
+    }
+}
+
+/// Size of a fixed-width value type; `None` for strings and arrays.
+fn fixed_size(value_type: u32) -> Option<u64> {
+    match value_type {
+        TYPE_U8 | TYPE_I8 | TYPE_BOOL => Some(1),
+        TYPE_U16 | TYPE_I16 => Some(2),
+        TYPE_U32 | TYPE_I32 | TYPE_F32 => Some(4),
+        TYPE_U64 | TYPE_I64 | TYPE_F64 => Some(8),
+        _ => None,
+    }
+}
+
+/// llama.cpp's name for a `general.file_type` value.
+fn quantization_name(file_type: u64) -> Option<&'static str> {
+    Some(match file_type {
+        0 => "F32",
+        1 => "F16",
+        2 => "Q4_0",
+        3 => "Q4_1",
+        7 => "Q8_0",
+        8 => "Q5_0",
+        9 => "Q5_1",
+        10 => "Q2_K",
+        11 => "Q3_K_S",
+        12 => "Q3_K_M",
+        13 => "Q3_K_L",
+        14 => "Q4_K_S",
+        15 => "Q4_K_M",
+        16 => "Q5_K_S",
+        17 => "Q5_K_M",
+        18 => "Q6_K",
+        19 => "IQ2_XXS",
+        20 => "IQ2_XS",
+        21 => "Q2_K_S",
+        22 => "IQ3_XS",
+        23 => "IQ3_XXS",
+        24 => "IQ1_S",
+        25 => "IQ4_NL",
+        26 => "IQ3_S",
+        27 => "IQ3_M",
+        28 => "IQ2_S",
+        29 => "IQ2_M",
+        30 => "IQ4_XS",
+        31 => "IQ1_M",
+        32 => "BF16",
+        _ => return None,
+    })
+}
+
+fn parse_header(source: impl Read) -> Result<ModelInfo, String> {
+    let mut reader = Reader { inner: source };
+    let magic: [u8; 4] = reader
+        .bytes()
+        .map_err(|_| "Too short to be a GGUF model: the download may be incomplete".to_string())?;
+    if &magic != GGUF_MAGIC {
+        return Err(format!(
+            "Not a GGUF model (starts with {:02x?} instead of \"GGUF\"): the file may be corrupt",
+            magic
+        ));
+    }
+    let version = reader.u32()?;
+    if version < 2 {
+        return Err(format!("GGUF version {} is not supported", version));
+    }
+    let tensor_count = reader.u64()?;
+    let kv_count = reader.u64()?;
+
+    let mut info = ModelInfo {
+        version,
+        tensor_count,
+        ..ModelInfo::default()
+    };
+    let mut context_lengths = Vec::new();
+    for _ in 0..kv_count {
+        let key = reader.string()?;
+        let value_type = reader.u32()?;
+        match (key.as_str(), reader.value(value_type, 0)?) {
+            ("general.architecture", Value::Text(arch)) => info.architecture = Some(arch),
+            ("general.name", Value::Text(name)) => info.name = Some(name),
+            ("general.file_type", Value::Int(file_type)) => {
+                info.quantization = quantization_name(file_type).map(str::to_string)
+            }
+            (key, Value::Int(len)) if key.ends_with(".context_length") => {
+                context_lengths.push((key.to_string(), len))
+            }
+            _ => {}
+        }
+    }
+    // Other architectures' keys can appear too; the model's own one counts.
+    info.context_length = info.architecture.as_ref().and_then(|arch| {
+        let own = format!("{}.context_length", arch);
+        context_lengths
+            .iter()
+            .find(|(key, _)| *key == own)
+            .map(|(_, len)| *len)
+    });
+
+    for _ in 0..tensor_count {
+        reader.string()?;
+        let dims = reader.u32()?;
+        let mut elements: u64 = 1;
+        for _ in 0..dims {
+            elements = elements.saturating_mul(reader.u64()?);
+        }
+        reader.skip(4 + 8)?; // type, offset
+        info.parameter_count = info.parameter_count.saturating_add(elements);
+    }
+    Ok(info)
+}
+
+pub(crate) fn read_model_info(path: &Path) -> Result<ModelInfo, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("Failed to open model: {}", e))?;
+    parse_header(BufReader::new(file.take(MAX_HEADER_BYTES)))
+}
+
+/// Header metadata of the active model. Fails with `NotFound` before it's
+/// downloaded, and with `Download` when the file isn't a readable GGUF (a
+/// truncated or corrupt download).
+#[tauri::command]
+pub(crate) async fn get_model_info(app: tauri::AppHandle) -> Result<ModelInfo, AppError> {
+    let path = crate::long_path(&crate::get_model_path(&app)?);
+    if !path.exists() {
+        return Err(AppError::NotFound("Model is not downloaded".to_string()));
+    }
+    tokio::task::spawn_blocking(move || read_model_info(&path))
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))?
+        .map_err(AppError::Download)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(out: &mut Vec<u8>, text: &str) {
+        out.extend_from_slice(&(text.len() as u64).to_le_bytes());
+        out.extend_from_slice(text.as_bytes());
+    }
+
+    #[test]
+    fn test_parse_header() {
+        let mut file = GGUF_MAGIC.to_vec();
+        file.extend_from_slice(&3u32.to_le_bytes());
+        file.extend_from_slice(&2u64.to_le_bytes()); // tensors
+        file.extend_from_slice(&5u64.to_le_bytes()); // key/values
+        string(&mut file, "general.architecture");
+        file.extend_from_slice(&TYPE_STRING.to_le_bytes());
+        string(&mut file, "llama");
+        string(&mut file, "tokenizer.ggml.tokens");
+        file.extend_from_slice(&TYPE_ARRAY.to_le_bytes());
+        file.extend_from_slice(&TYPE_STRING.to_le_bytes());
+        file.extend_from_slice(&2u64.to_le_bytes());
+        string(&mut file, "<s>");
+        string(&mut file, "</s>");
+        string(&mut file, "clip.context_length");
+        file.extend_from_slice(&TYPE_U32.to_le_bytes());
+        file.extend_from_slice(&77u32.to_le_bytes());
+        string(&mut file, "llama.context_length");
+        file.extend_from_slice(&TYPE_U32.to_le_bytes());
+        file.extend_from_slice(&8192u32.to_le_bytes());
+        string(&mut file, "general.file_type");
+        file.extend_from_slice(&TYPE_U32.to_le_bytes());
+        file.extend_from_slice(&15u32.to_le_bytes());
+        for (name, dims) in [("token_embd.weight", &[4096u64, 32][..]), ("norm", &[4096])] {
+            string(&mut file, name);
+            file.extend_from_slice(&(dims.len() as u32).to_le_bytes());
+            for dim in dims {
+                file.extend_from_slice(&dim.to_le_bytes());
+            }
+            file.extend_from_slice(&[0u8; 12]);
+        }
+
+        let info = parse_header(&file[..]).unwrap();
+        assert_eq!(
+            info,
+            ModelInfo {
+                version: 3,
+                name: None,
+                architecture: Some("llama".to_string()),
+                context_length: Some(8192),
+                quantization: Some("Q4_K_M".to_string()),
+                parameter_count: 4096 * 32 + 4096,
+                tensor_count: 2,
+            }
+        );
+
+        let truncated = parse_header(&file[..file.len() - 20]).unwrap_err();
+        assert!(truncated.contains("truncated"), "{}", truncated);
+        let html = parse_header(&b"<!DOCTYPE html>"[..]).unwrap_err();
+        assert!(html.contains("Not a GGUF model"), "{}", html);
+    }
+
+    #[test]
+    fn test_deeply_nested_arrays_are_rejected() {
+        let mut file = GGUF_MAGIC.to_vec();
+        file.extend_from_slice(&3u32.to_le_bytes());
+        file.extend_from_slice(&0u64.to_le_bytes()); // tensors
+        file.extend_from_slice(&1u64.to_le_bytes()); // key/values
+        string(&mut file, "nested");
+        file.extend_from_slice(&TYPE_ARRAY.to_le_bytes());
+        // Each level is an array holding one array; far deeper than the stack
+        // would survive if recursion weren't capped.
+        for _ in 0..1_000_000 {
+            file.extend_from_slice(&TYPE_ARRAY.to_le_bytes());
+            file.extend_from_slice(&1u64.to_le_bytes());
+        }
+
+        let err = parse_header(&file[..]).unwrap_err();
+        assert!(err.contains("Corrupt GGUF header"), "{}", err);
+    }
+}
@@ -0,0 +1,332 @@
+//! SHA-256 hashing and verification of files on disk.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use tauri::{Emitter, Manager, State};
+
+use crate::error::AppError;
+use crate::DownloadState;
+
+/// `sha256sum`-format manifest of the bundled gateway, produced by
+/// scripts/bundle-gateway.sh and embedded by build.rs. Empty in dev builds.
+const GATEWAY_MANIFEST: &str = include_str!(concat!(env!("OUT_DIR"), "/gateway-manifest.sha256"));
+
+const HASH_BUF_SIZE: usize = 1024 * 1024;
+/// Minimum gap between `verify-progress` events.
+const PROGRESS_INTERVAL_MS: u128 = 200;
+
+/// Streams `path` through SHA-256 and returns the lowercase hex digest.
+pub(crate) fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut hasher = Sha256::new();
+    hash_file_into(path, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Feeds the whole of `path` into `hasher`, e.g. to catch up on the part of a
+/// resumed download that was written in an earlier session.
+pub(crate) fn hash_file_into(path: &Path, hasher: &mut Sha256) -> std::io::Result<()> {
+    hash_file_with_progress(path, hasher, |_| {})
+}
+
+/// Feeds bytes `from..to` of `path` into `hasher`, for hashing a file that's
+/// still being written as its finished part grows.
+pub(crate) fn hash_range_into(
+    path: &Path,
+    hasher: &mut Sha256,
+    from: u64,
+    to: u64,
+) -> std::io::Result<()> {
+    let mut file = std::fs::File::open(path)?;
+    file.seek(SeekFrom::Start(from))?;
+    let mut range = file.take(to.saturating_sub(from));
+    let mut buf = vec![0u8; HASH_BUF_SIZE];
+    loop {
+        let n = range.read(&mut buf)?;
+        if n == 0 {
+            return Ok(());
+        }
+        hasher.update(&buf[..n]);
+    }
+}
+
+/// `hash_file_into`, reporting the bytes hashed so far to `on_progress`.
+fn hash_file_with_progress(
+    path: &Path,
+    hasher: &mut Sha256,
+    mut on_progress: impl FnMut(u64),
+) -> std::io::Result<()> {
+    let mut file = std::fs::File::open(path)?;
+    let mut buf = vec![0u8; HASH_BUF_SIZE];
+    let mut hashed = 0u64;
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            return Ok(());
+        }
+        hasher.update(&buf[..n]);
+        hashed += n as u64;
+        on_progress(hashed);
+    }
+}
+
+/// Copies `src` to `dst` through a `.importing` staging file, hashing on the
+/// way, then re-hashes the copy before renaming it into place. `on_progress`
+/// gets the bytes copied so far. Returns the SHA-256 of the data.
+pub(crate) fn copy_with_verify(
+    src: &Path,
+    dst: &Path,
+    mut on_progress: impl FnMut(u64),
+) -> Result<String, String> {
+    let mut staging = dst.as_os_str().to_os_string();
+    staging.push(".importing");
+    let staging = std::path::PathBuf::from(staging);
+
+    let copied = (|| -> std::io::Result<String> {
+        let mut input = std::fs::File::open(src)?;
+        let mut output = std::fs::File::create(&staging)?;
+        let mut hasher = Sha256::new();
+        let mut buf = vec![0u8; HASH_BUF_SIZE];
+        let mut copied = 0u64;
+        loop {
+            let n = input.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            output.write_all(&buf[..n])?;
+            hasher.update(&buf[..n]);
+            copied += n as u64;
+            on_progress(copied);
+        }
+        output.sync_all()?;
+        Ok(hex::encode(hasher.finalize()))
+    })();
+    let result = copied.map_err(|e| e.to_string()).and_then(|digest| {
+        let written = sha256_file(&staging).map_err(|e| e.to_string())?;
+        if written != digest {
+            return Err("Copy does not match the source (disk error?)".to_string());
+        }
+        std::fs::rename(&staging, dst).map_err(|e| e.to_string())?;
+        Ok(digest)
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&staging);
+    }
+    result
+}
+
+/// Parses `<hex digest>  ./relative/path` lines.
+fn parse_manifest(manifest: &str) -> Vec<(String, String)> {
+    manifest
+        .lines()
+        .filter_map(|line| {
+            let (hash, path) = line.split_once(char::is_whitespace)?;
+            // sha256sum marks binary-mode entries with a leading '*'.
+            let path = path.trim_start().trim_start_matches('*');
+            let path = path.strip_prefix("./").unwrap_or(path);
+            Some((hash.to_ascii_lowercase(), path.to_string()))
+        })
+        .collect()
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum FileIntegrity {
+    Ok,
+    Modified,
+    Missing,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct FileCheck {
+    pub path: String,
+    pub status: FileIntegrity,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum InstallVerdict {
+    /// Every file in the manifest is present and matches.
+    Intact,
+    /// At least one file is missing or differs from the manifest.
+    Compromised,
+    /// No embedded manifest (dev build), so there is nothing to check against.
+    Unverified,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct InstallIntegrityReport {
+    pub verdict: InstallVerdict,
+    pub files_checked: usize,
+    /// Only the files that failed; listing thousands of OK node_modules
+    /// entries helps nobody.
+    pub failures: Vec<FileCheck>,
+}
+
+fn check_against_manifest(root: &Path, manifest: &str) -> InstallIntegrityReport {
+    let entries = parse_manifest(manifest);
+    if entries.is_empty() {
+        return InstallIntegrityReport {
+            verdict: InstallVerdict::Unverified,
+            files_checked: 0,
+            failures: Vec::new(),
+        };
+    }
+
+    let failures: Vec<FileCheck> = entries
+        .iter()
+        .filter_map(|(expected, rel)| {
+            let status = match sha256_file(&root.join(rel)) {
+                Ok(actual) if actual == *expected => return None,
+                Ok(_) => FileIntegrity::Modified,
+                Err(_) => FileIntegrity::Missing,
+            };
+            Some(FileCheck {
+                path: rel.clone(),
+                status,
+            })
+        })
+        .collect();
+
+    InstallIntegrityReport {
+        verdict: if failures.is_empty() {
+            InstallVerdict::Intact
+        } else {
+            InstallVerdict::Compromised
+        },
+        files_checked: entries.len(),
+        failures,
+    }
+}
+
+/// Hashes the bundled gateway in the resource directory and compares it to
+/// the manifest embedded at build time.
+#[tauri::command]
+pub(crate) async fn verify_install_integrity(
+    app: tauri::AppHandle,
+) -> Result<InstallIntegrityReport, AppError> {
+    let root = app.path().resource_dir()?.join("resources/gateway");
+    tokio::task::spawn_blocking(move || check_against_manifest(&root, GATEWAY_MANIFEST))
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct ModelVerification {
+    pub exists: bool,
+    pub size: u64,
+    /// Size the server reports; `None` when it couldn't be reached.
+    pub expected_size: Option<u64>,
+    /// `None` when no expected digest is known (nothing pinned and the server
+    /// unreachable or not advertising one).
+    pub hash_ok: Option<bool>,
+    pub sha256: Option<String>,
+    pub expected_sha256: Option<String>,
+}
+
+/// Payload of `verify-progress`.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+struct VerifyProgress {
+    hashed: u64,
+    total: u64,
+}
+
+/// Re-hashes the model on disk and compares it to the pinned digest, or else
+/// the one the server advertises. Emits `verify-progress` while hashing.
+#[tauri::command]
+pub(crate) async fn verify_model(
+    app: tauri::AppHandle,
+    download: State<'_, DownloadState>,
+) -> Result<ModelVerification, AppError> {
+    if crate::download_running(&download)? {
+        return Err(AppError::Busy(
+            "Cannot verify the model while it is downloading".to_string(),
+        ));
+    }
+    let model = crate::active_model(&app);
+    let path = crate::long_path(&crate::model_path(&app, model)?);
+    let Ok(metadata) = path.metadata() else {
+        return Ok(ModelVerification {
+            exists: false,
+            size: 0,
+            expected_size: None,
+            hash_ok: None,
+            sha256: None,
+            expected_sha256: None,
+        });
+    };
+    let size = metadata.len();
+
+    let remote = match crate::build_download_client(&app) {
+        Ok(client) => crate::probe_mirrors(&client, &crate::model_download_urls(&app, model))
+            .await
+            .map_err(|e| tracing::info!("Verifying without the server: {}", e))
+            .ok(),
+        Err(_) => None,
+    };
+    let expected_sha256 = model
+        .sha256
+        .map(str::to_string)
+        .or_else(|| remote.as_ref().and_then(|r| r.sha256.clone()));
+
+    let hash_app = app.clone();
+    let digest = tokio::task::spawn_blocking(move || {
+        let mut hasher = Sha256::new();
+        let mut last_emit = std::time::Instant::now();
+        hash_file_with_progress(&path, &mut hasher, |hashed| {
+            if last_emit.elapsed().as_millis() > PROGRESS_INTERVAL_MS || hashed == size {
+                let _ = hash_app.emit(
+                    "verify-progress",
+                    VerifyProgress {
+                        hashed,
+                        total: size,
+                    },
+                );
+                last_emit = std::time::Instant::now();
+            }
+        })
+        .map(|_| hex::encode(hasher.finalize()))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| AppError::Io(format!("Failed to hash model: {}", e)))?;
+
+    let hash_ok = expected_sha256.as_ref().map(|expected| *expected == digest);
+    tracing::info!("Model verification: hash ok = {:?}", hash_ok);
+    Ok(ModelVerification {
+        exists: true,
+        size,
+        expected_size: remote.map(|r| r.total_size),
+        hash_ok,
+        sha256: Some(digest),
+        expected_sha256,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_against_manifest() {
+        let root = std::env::temp_dir().join("openmoose-integrity-test");
+        std::fs::create_dir_all(root.join("gateway")).unwrap();
+        std::fs::write(root.join("gateway/server.js"), b"hello").unwrap();
+
+        // sha256("hello")
+        let manifest = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  ./gateway/server.js\n\
+                        2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  ./gateway/gone.js\n";
+        let report = check_against_manifest(&root, manifest);
+        assert_eq!(report.verdict, InstallVerdict::Compromised);
+        assert_eq!(report.files_checked, 2);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].path, "gateway/gone.js");
+        assert_eq!(report.failures[0].status, FileIntegrity::Missing);
+
+        assert_eq!(
+            check_against_manifest(&root, "").verdict,
+            InstallVerdict::Unverified
+        );
+    }
+}
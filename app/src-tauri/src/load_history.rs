@@ -0,0 +1,195 @@
+//! Remembers whether the gateway managed to run inference on the current model
+//! (`~/.moose/load-history.json`), so an unchanged model can skip the slow
+//! verify and test steps on the next launch.
+//!
+//! The model is identified by its name, size and mtime together with the
+//! SHA-256 recorded when its download was verified (the `.sha256` file next to
+//! it), rather than by re-hashing several GB on every launch, which is exactly
+//! the cost this avoids. The mtime alone can't tell a re-download apart, since
+//! downloads take the upstream Last-Modified; the recorded digest can. A model
+//! with no recorded digest is never known good.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Runtime};
+
+use crate::error::AppError;
+
+const LOAD_HISTORY_FILENAME: &str = "load-history.json";
+/// Outcomes kept in `recent`; older ones are dropped.
+const MAX_LOAD_RECORDS: usize = 20;
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub(crate) struct ModelFingerprint {
+    pub filename: String,
+    pub size: u64,
+    pub modified_epoch_secs: u64,
+    /// Verified SHA-256 recorded by the download, if any.
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct LoadRecord {
+    pub at_epoch_secs: u64,
+    pub model: ModelFingerprint,
+    pub success: bool,
+    /// Error message for a failed load.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub(crate) struct LoadHistory {
+    #[serde(default)]
+    pub last_successful_load: Option<ModelFingerprint>,
+    /// Newest first.
+    #[serde(default)]
+    pub recent: Vec<LoadRecord>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct LoadHistoryReport {
+    #[serde(flatten)]
+    pub history: LoadHistory,
+    /// The model on disk is the one that last loaded successfully.
+    pub current_model_known_good: bool,
+}
+
+fn history_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    Ok(crate::get_moose_dir(app)?.join(LOAD_HISTORY_FILENAME))
+}
+
+fn now_epoch_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub(crate) fn fingerprint(path: &Path) -> Option<ModelFingerprint> {
+    let metadata = path.metadata().ok()?;
+    Some(ModelFingerprint {
+        filename: path.file_name()?.to_string_lossy().to_string(),
+        size: metadata.len(),
+        modified_epoch_secs: metadata
+            .modified()
+            .ok()?
+            .duration_since(UNIX_EPOCH)
+            .ok()?
+            .as_secs(),
+        sha256: crate::read_model_digest(path),
+    })
+}
+
+fn read_history<R: Runtime>(app: &AppHandle<R>) -> LoadHistory {
+    history_path(app)
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+impl LoadHistory {
+    fn record(&mut self, record: LoadRecord) {
+        if record.success {
+            self.last_successful_load = Some(record.model.clone());
+        } else if self.last_successful_load.as_ref() == Some(&record.model) {
+            // The same file failed this time, so it can no longer be trusted.
+            self.last_successful_load = None;
+        }
+        self.recent.insert(0, record);
+        self.recent.truncate(MAX_LOAD_RECORDS);
+    }
+
+    fn is_known_good(&self, current: Option<&ModelFingerprint>) -> bool {
+        current.is_some_and(|model| model.sha256.is_some())
+            && self.last_successful_load.as_ref() == current
+    }
+}
+
+/// Records whether inference on the current model worked. Best effort: a
+/// failure to persist only costs a slower next launch.
+pub(crate) fn record_load_outcome<R: Runtime>(app: &AppHandle<R>, result: Result<(), &str>) {
+    let Some(model) = crate::get_model_path(app)
+        .ok()
+        .and_then(|path| fingerprint(&path))
+    else {
+        return;
+    };
+    let mut history = read_history(app);
+    history.record(LoadRecord {
+        at_epoch_secs: now_epoch_secs(),
+        model,
+        success: result.is_ok(),
+        detail: result.err().map(str::to_string),
+    });
+    let written = history_path(app).and_then(|path| {
+        let content = serde_json::to_string_pretty(&history).map_err(|e| e.to_string())?;
+        std::fs::write(path, content).map_err(|e| e.to_string())
+    });
+    if let Err(e) = written {
+        tracing::warn!("Failed to save load history: {}", e);
+    }
+}
+
+/// Whether the model on disk is unchanged since it last loaded successfully.
+pub(crate) fn current_model_known_good<R: Runtime>(app: &AppHandle<R>) -> bool {
+    let current = crate::get_model_path(app)
+        .ok()
+        .and_then(|path| fingerprint(&path));
+    read_history(app).is_known_good(current.as_ref())
+}
+
+#[tauri::command]
+pub(crate) async fn get_load_history(app: tauri::AppHandle) -> Result<LoadHistoryReport, AppError> {
+    Ok(LoadHistoryReport {
+        current_model_known_good: current_model_known_good(&app),
+        history: read_history(&app),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(size: u64, success: bool) -> LoadRecord {
+        LoadRecord {
+            at_epoch_secs: 0,
+            model: ModelFingerprint {
+                filename: "model.gguf".to_string(),
+                size,
+                modified_epoch_secs: 1,
+                sha256: Some("ab".repeat(32)),
+            },
+            success,
+            detail: None,
+        }
+    }
+
+    #[test]
+    fn test_known_good_tracks_last_success() {
+        let mut history = LoadHistory::default();
+        history.record(record(10, true));
+        assert!(history.is_known_good(Some(&record(10, true).model)));
+        assert!(!history.is_known_good(Some(&record(11, true).model)));
+        assert!(!history.is_known_good(None));
+
+        // Same name, size and mtime, but a different verified digest.
+        let mut redownloaded = record(10, true).model;
+        redownloaded.sha256 = Some("cd".repeat(32));
+        assert!(!history.is_known_good(Some(&redownloaded)));
+
+        // Nothing recorded to compare against.
+        let mut unverified = record(10, true);
+        unverified.model.sha256 = None;
+        let mut unverified_history = LoadHistory::default();
+        unverified_history.record(unverified.clone());
+        assert!(!unverified_history.is_known_good(Some(&unverified.model)));
+
+        history.record(record(10, false));
+        assert!(!history.is_known_good(Some(&record(10, true).model)));
+        assert_eq!(history.recent.len(), 2);
+    }
+}
@@ -0,0 +1,226 @@
+//! Bringing models over from another OpenMoose install (`import_from_moose_dir`),
+//! e.g. a `.moose` directory copied from an old machine, instead of
+//! re-downloading them.
+
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+
+use crate::error::AppError;
+use crate::gguf::GGUF_MAGIC;
+
+/// Where models live inside a moose data directory.
+const MODELS_SUBDIR: &str = "models/llama-cpp";
+/// Minimum gap between `import-progress` events.
+const PROGRESS_INTERVAL_MS: u128 = 200;
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "status", content = "reason", rename_all = "snake_case")]
+pub(crate) enum ImportStatus {
+    Imported,
+    /// A model with that name is already installed; it is left alone.
+    Duplicate,
+    /// Not a usable model (partial download, wrong format, bad checksum).
+    Invalid(String),
+    Failed(String),
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct ImportedModel {
+    pub name: String,
+    pub size: u64,
+    #[serde(flatten)]
+    pub status: ImportStatus,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct ImportSummary {
+    pub models: Vec<ImportedModel>,
+    pub imported: usize,
+    pub bytes_copied: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+struct ImportProgress {
+    name: String,
+    copied: u64,
+    total: u64,
+}
+
+/// The models directory of a moose data directory, accepting either the
+/// `.moose` directory itself or its `models/llama-cpp` subdirectory.
+fn source_models_dir(source: &Path) -> Result<PathBuf, String> {
+    if source.join(MODELS_SUBDIR).is_dir() {
+        return Ok(source.join(MODELS_SUBDIR));
+    }
+    if source.ends_with(MODELS_SUBDIR) && source.is_dir() {
+        return Ok(source.to_path_buf());
+    }
+    Err(format!(
+        "{} does not look like an OpenMoose data directory (no {})",
+        source.display(),
+        MODELS_SUBDIR
+    ))
+}
+
+/// Checks a candidate's size and magic bytes; the checksum, if any, is
+/// checked against the copy since that reads the whole file anyway.
+fn validate_candidate(path: &Path, size: u64) -> Result<(), String> {
+    if crate::download_meta_path(path).exists() {
+        return Err("Incomplete download".to_string());
+    }
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let known = crate::MODEL_REGISTRY.iter().find(|m| m.filename == name);
+    if let Some(model) = known.filter(|m| size <= m.min_size) {
+        return Err(format!(
+            "Only {} bytes, expected more than {}",
+            size, model.min_size
+        ));
+    }
+    let mut magic = [0u8; 4];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .map_err(|_| "Too short to be a GGUF model".to_string())?;
+    if &magic != GGUF_MAGIC {
+        return Err("Not a GGUF model".to_string());
+    }
+    Ok(())
+}
+
+fn import_one(
+    app: &tauri::AppHandle,
+    src: &Path,
+    dest_dir: &Path,
+    name: &str,
+    size: u64,
+) -> ImportStatus {
+    let dest = dest_dir.join(name);
+    if dest.exists() {
+        return ImportStatus::Duplicate;
+    }
+    if let Err(reason) = validate_candidate(src, size) {
+        return ImportStatus::Invalid(reason);
+    }
+
+    let mut last_emit = std::time::Instant::now();
+    let progress = |copied: u64| {
+        if last_emit.elapsed().as_millis() > PROGRESS_INTERVAL_MS || copied == size {
+            let _ = app.emit(
+                "import-progress",
+                ImportProgress {
+                    name: name.to_string(),
+                    copied,
+                    total: size,
+                },
+            );
+            last_emit = std::time::Instant::now();
+        }
+    };
+    let digest = match crate::integrity::copy_with_verify(src, &dest, progress) {
+        Ok(digest) => digest,
+        Err(e) => return ImportStatus::Failed(e),
+    };
+    match crate::read_model_digest(src) {
+        Some(expected) if expected != digest => {
+            let _ = std::fs::remove_file(&dest);
+            ImportStatus::Invalid("SHA-256 does not match its .sha256 file".to_string())
+        }
+        _ => ImportStatus::Imported,
+    }
+}
+
+fn import_models(
+    app: &tauri::AppHandle,
+    source: &Path,
+    dest_dir: &Path,
+) -> Result<ImportSummary, String> {
+    let source_dir = source_models_dir(source)?;
+    if source_dir.canonicalize().ok() == dest_dir.canonicalize().ok() {
+        return Err("That is this install's own data directory".to_string());
+    }
+    std::fs::create_dir_all(dest_dir).map_err(|e| e.to_string())?;
+
+    let mut candidates: Vec<(String, PathBuf, u64)> = std::fs::read_dir(&source_dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let metadata = entry.metadata().ok()?;
+            (metadata.is_file() && name.to_ascii_lowercase().ends_with(".gguf")).then_some((
+                name,
+                path,
+                metadata.len(),
+            ))
+        })
+        .collect();
+    candidates.sort();
+
+    let mut summary = ImportSummary {
+        models: Vec::new(),
+        imported: 0,
+        bytes_copied: 0,
+    };
+    for (name, path, size) in candidates {
+        let status = import_one(app, &path, dest_dir, &name, size);
+        tracing::info!("Import of {}: {:?}", name, status);
+        if status == ImportStatus::Imported {
+            summary.imported += 1;
+            summary.bytes_copied += size;
+        }
+        summary.models.push(ImportedModel { name, size, status });
+    }
+    Ok(summary)
+}
+
+/// Copies the valid models from another install's data directory into this
+/// one's, skipping names that are already installed. Emits `import-progress`.
+#[tauri::command]
+pub(crate) async fn import_from_moose_dir(
+    app: tauri::AppHandle,
+    source_dir: String,
+) -> Result<ImportSummary, AppError> {
+    let dest_dir = crate::get_moose_dir(&app)?.join(MODELS_SUBDIR);
+    tauri::async_runtime::spawn_blocking(move || {
+        import_models(&app, Path::new(&source_dir), &dest_dir)
+    })
+    .await?
+    .map_err(AppError::Io)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_candidate() {
+        let dir = std::env::temp_dir().join("openmoose-import-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(source_models_dir(&dir).is_err());
+
+        let model = dir.join("small.gguf");
+        std::fs::write(&model, b"GGUFdata").unwrap();
+        assert_eq!(validate_candidate(&model, 8), Ok(()));
+
+        let not_gguf = dir.join("notes.gguf");
+        std::fs::write(&not_gguf, b"<html>").unwrap();
+        assert!(validate_candidate(&not_gguf, 6).is_err());
+
+        std::fs::write(crate::download_meta_path(&model), b"{}").unwrap();
+        assert_eq!(
+            validate_candidate(&model, 8),
+            Err("Incomplete download".to_string())
+        );
+
+        // Some tools write the digest uppercase.
+        let digest = crate::integrity::sha256_file(&model).unwrap();
+        std::fs::write(
+            dir.join("small.gguf.sha256"),
+            format!("{}  small.gguf\n", digest.to_ascii_uppercase()),
+        )
+        .unwrap();
+        assert_eq!(crate::read_model_digest(&model), Some(digest));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
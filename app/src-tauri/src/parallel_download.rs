@@ -0,0 +1,688 @@
+//! Multi-connection model download: the file is split into segments fetched
+//! over several range requests at once and written at their offsets.
+//!
+//! Progress per segment is kept in a `<model>.ranges` sidecar, so a paused or
+//! interrupted download only re-fetches what's missing. Segments are handed out
+//! from a shared queue; one whose connection fails goes back on the queue with
+//! its remaining bytes for whichever connection is free next.
+//!
+//! The digest is built while the download runs: the hasher follows the
+//! contiguous finished prefix of the file, so when the last segment lands
+//! only the bytes past that prefix are still left to read.
+
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::download_trace::DownloadTrace;
+use crate::{integrity, DownloadControl, ModelClient, RemoteModel, Throttle};
+
+pub(crate) const DEFAULT_MAX_CONNECTIONS: u32 = 4;
+/// Hard ceiling on `download_max_connections`; mirrors tend to throttle or
+/// drop clients that open more.
+pub(crate) const CONNECTION_LIMIT: u32 = 16;
+/// Segments are at most this big, so a slow or failed connection only holds
+/// up a small part of the file.
+const MAX_SEGMENT_SIZE: u64 = 64 * 1024 * 1024;
+/// Aim for this many segments per connection, so work stays balanced.
+const SEGMENTS_PER_CONNECTION: u64 = 4;
+/// Failed segment fetches tolerated across the whole download before giving up.
+const MAX_SEGMENT_FAILURES: u32 = 10;
+/// How often aggregated progress is emitted and the sidecar saved.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub(crate) struct Segment {
+    pub start: u64,
+    /// Exclusive.
+    pub end: u64,
+    /// Bytes written from `start` onwards.
+    pub done: u64,
+}
+
+impl Segment {
+    fn is_complete(&self) -> bool {
+        self.start + self.done >= self.end
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct RangesSidecar {
+    total_size: u64,
+    segments: Vec<Segment>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum RangeState {
+    Active,
+    Done,
+    /// The connection failed; the rest of the segment is back on the queue.
+    Retrying,
+}
+
+/// Payload of `download-range-status`.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+struct RangeStatus {
+    index: usize,
+    start: u64,
+    end: u64,
+    done: u64,
+    worker: usize,
+    state: RangeState,
+}
+
+pub(crate) enum ParallelOutcome {
+    /// Finished, with the file's SHA-256.
+    Completed(String),
+    Paused(u64),
+    Cancelled(u64),
+}
+
+pub(crate) fn ranges_path(file_path: &Path) -> PathBuf {
+    let mut name = file_path.as_os_str().to_os_string();
+    name.push(".ranges");
+    PathBuf::from(name)
+}
+
+/// Splits `[prefix, total)` into segments; `prefix` bytes are already on disk
+/// (e.g. from an earlier single-stream attempt).
+fn plan_segments(total: u64, prefix: u64, connections: u32) -> Vec<Segment> {
+    let size = total
+        .div_ceil(connections as u64 * SEGMENTS_PER_CONNECTION)
+        .clamp(1, MAX_SEGMENT_SIZE);
+    let mut segments = Vec::new();
+    let mut start = prefix.min(total);
+    while start < total {
+        let end = (start + size).min(total);
+        segments.push(Segment {
+            start,
+            end,
+            done: 0,
+        });
+        start = end;
+    }
+    segments
+}
+
+fn load_sidecar(path: &Path, total_size: u64) -> Option<Vec<Segment>> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let sidecar: RangesSidecar = serde_json::from_str(&content).ok()?;
+    (sidecar.total_size == total_size).then_some(sidecar.segments)
+}
+
+fn save_sidecar(path: &Path, total_size: u64, segments: &[Segment]) {
+    let sidecar = RangesSidecar {
+        total_size,
+        segments: segments.to_vec(),
+    };
+    let written = serde_json::to_string(&sidecar)
+        .map_err(|e| e.to_string())
+        .and_then(|content| std::fs::write(path, content).map_err(|e| e.to_string()));
+    if let Err(e) = written {
+        tracing::warn!("Failed to save download ranges: {}", e);
+    }
+}
+
+/// End of the contiguous run of finished bytes from the start of the file.
+/// Whatever precedes the first segment was written before it was planned.
+fn finished_prefix(segments: &[Segment], total_size: u64) -> u64 {
+    let mut prefix = segments.first().map_or(total_size, |s| s.start);
+    for segment in segments {
+        if segment.start > prefix {
+            break;
+        }
+        prefix = segment.start + segment.done.min(segment.end - segment.start);
+        if !segment.is_complete() {
+            break;
+        }
+    }
+    prefix
+}
+
+/// Hashes `path` from `hashed` up to `to`, off the async runtime.
+async fn advance_hash(
+    path: &Path,
+    mut hasher: Sha256,
+    hashed: u64,
+    to: u64,
+) -> Result<Sha256, String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        integrity::hash_range_into(&path, &mut hasher, hashed, to).map(|_| hasher)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("Failed to hash model: {}", e))
+}
+
+/// Bytes already fetched by an interrupted parallel download, if there is one.
+pub(crate) fn bytes_done(file_path: &Path) -> Option<u64> {
+    let content = std::fs::read_to_string(ranges_path(file_path)).ok()?;
+    let sidecar: RangesSidecar = serde_json::from_str(&content).ok()?;
+    let pending: u64 = sidecar
+        .segments
+        .iter()
+        .map(|s| s.end - s.start - s.done)
+        .sum();
+    Some(sidecar.total_size - pending)
+}
+
+#[cfg(unix)]
+fn write_at(file: &std::fs::File, buf: &[u8], offset: u64) -> std::io::Result<()> {
+    std::os::unix::fs::FileExt::write_all_at(file, buf, offset)
+}
+
+#[cfg(windows)]
+fn write_at(file: &std::fs::File, mut buf: &[u8], mut offset: u64) -> std::io::Result<()> {
+    while !buf.is_empty() {
+        let n = std::os::windows::fs::FileExt::seek_write(file, buf, offset)?;
+        buf = &buf[n..];
+        offset += n as u64;
+    }
+    Ok(())
+}
+
+/// Start and inclusive end of a `Content-Range: bytes 100-199/12345` header.
+fn parse_content_range(value: &str) -> Option<(u64, u64)> {
+    let range = value.trim().strip_prefix("bytes ")?.split('/').next()?;
+    let (start, end) = range.split_once('-')?;
+    Some((start.trim().parse().ok()?, end.trim().parse().ok()?))
+}
+
+/// Whether `url` answers a one-byte range request with 206.
+async fn supports_ranges(client: &ModelClient, url: &str) -> bool {
+    client
+        .get(url)
+        .header(reqwest::header::RANGE, "bytes=0-0")
+        .send()
+        .await
+        .map(|res| res.status() == reqwest::StatusCode::PARTIAL_CONTENT)
+        .unwrap_or(false)
+}
+
+/// State shared by the connections of one download.
+struct Shared {
+    segments: Mutex<Vec<Segment>>,
+    queue: Mutex<VecDeque<usize>>,
+    downloaded: AtomicU64,
+    failures: AtomicU32,
+    /// Set when a connection gives up, so the others stop too.
+    failed: AtomicBool,
+    throttle: Mutex<Option<Throttle>>,
+}
+
+impl Shared {
+    fn should_stop(&self, control: &DownloadControl) -> bool {
+        self.failed.load(Ordering::SeqCst)
+            || control.pause.load(Ordering::SeqCst)
+            || control.cancel.load(Ordering::SeqCst)
+    }
+
+    fn emit_status<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        index: usize,
+        worker: usize,
+        state: RangeState,
+    ) {
+        let Some(segment) = self.segments.lock().ok().map(|s| s[index].clone()) else {
+            return;
+        };
+        let _ = app.emit(
+            "download-range-status",
+            RangeStatus {
+                index,
+                start: segment.start,
+                end: segment.end,
+                done: segment.done,
+                worker,
+                state,
+            },
+        );
+    }
+}
+
+/// Fetches the rest of segment `index`. Returns `Ok(false)` if it stopped
+/// early for a pause, cancel or another connection's failure.
+async fn fetch_segment<R: Runtime>(
+    app: &AppHandle<R>,
+    client: &ModelClient,
+    url: &str,
+    file: &std::fs::File,
+    shared: &Shared,
+    index: usize,
+) -> Result<bool, String> {
+    let control = app.state::<DownloadControl>();
+    let (mut offset, end) = {
+        let segments = shared.segments.lock().map_err(|e| e.to_string())?;
+        let segment = &segments[index];
+        (segment.start + segment.done, segment.end)
+    };
+    let res = client
+        .get(url)
+        .header(
+            reqwest::header::RANGE,
+            format!("bytes={}-{}", offset, end - 1),
+        )
+        .send()
+        .await
+        .map_err(|e| format!("Range request failed: {}", e))?;
+    if let Some(e) = client.auth_error(res.status(), url) {
+        return Err(e);
+    }
+    if res.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        return Err(format!("Range request returned {}", res.status()));
+    }
+    // Bytes are written at the offset asked for, so a server answering with
+    // some other range would corrupt the file.
+    let served = res
+        .headers()
+        .get(reqwest::header::CONTENT_RANGE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    if served.as_deref().and_then(parse_content_range) != Some((offset, end - 1)) {
+        return Err(format!(
+            "Range request for bytes {}-{} was answered with {}",
+            offset,
+            end - 1,
+            served.unwrap_or_else(|| "no Content-Range".to_string())
+        ));
+    }
+
+    let mut stream = res.bytes_stream();
+    while offset < end {
+        let Some(item) = stream.next().await else {
+            return Err(format!("Connection closed {} bytes early", end - offset));
+        };
+        let chunk = item.map_err(|e| format!("Range stream failed: {}", e))?;
+        let chunk = &chunk[..chunk.len().min((end - offset) as usize)];
+        write_at(file, chunk, offset).map_err(|e| e.to_string())?;
+        offset += chunk.len() as u64;
+        if let Ok(mut segments) = shared.segments.lock() {
+            segments[index].done += chunk.len() as u64;
+        }
+        shared
+            .downloaded
+            .fetch_add(chunk.len() as u64, Ordering::SeqCst);
+
+        let delay = shared
+            .throttle
+            .lock()
+            .ok()
+            .and_then(|mut t| {
+                t.as_mut()
+                    .map(|t| t.delay_after(chunk.len() as u64, std::time::Instant::now()))
+            })
+            .unwrap_or_default();
+        crate::sleep_unless_stopped(&control, delay).await;
+        if shared.should_stop(&control) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// One connection: takes segments off the queue until it's empty.
+async fn run_worker<R: Runtime>(
+    app: &AppHandle<R>,
+    client: &ModelClient,
+    url: &str,
+    file: &std::fs::File,
+    shared: &Shared,
+    worker: usize,
+) -> Result<(), String> {
+    let control = app.state::<DownloadControl>();
+    loop {
+        if shared.should_stop(&control) {
+            return Ok(());
+        }
+        let Some(index) = shared.queue.lock().ok().and_then(|mut q| q.pop_front()) else {
+            return Ok(());
+        };
+        shared.emit_status(app, index, worker, RangeState::Active);
+        match fetch_segment(app, client, url, file, shared, index).await {
+            Ok(true) => shared.emit_status(app, index, worker, RangeState::Done),
+            Ok(false) => return Ok(()),
+            Err(e) => {
+                let failures = shared.failures.fetch_add(1, Ordering::SeqCst) + 1;
+                tracing::warn!(
+                    "Segment {} failed on connection {} ({}/{}): {}",
+                    index,
+                    worker,
+                    failures,
+                    MAX_SEGMENT_FAILURES,
+                    e
+                );
+                if failures >= MAX_SEGMENT_FAILURES {
+                    shared.failed.store(true, Ordering::SeqCst);
+                    return Err(format!(
+                        "Parallel download failed after {} errors: {}",
+                        failures, e
+                    ));
+                }
+                // Back on the queue for any free connection, this one included.
+                if let Ok(mut queue) = shared.queue.lock() {
+                    queue.push_back(index);
+                }
+                shared.emit_status(app, index, worker, RangeState::Retrying);
+                tokio::time::sleep(crate::retry_backoff(failures)).await;
+            }
+        }
+    }
+}
+
+/// Downloads the model over up to `connections` range requests. Returns
+/// `None` when the single-stream path should be used instead: parallelism is
+/// off and there's no parallel download to resume, or the server doesn't
+/// honor ranges (in which case an unfinishable partial file is removed).
+pub(crate) async fn download<R: Runtime>(
+    app: &AppHandle<R>,
+    client: &ModelClient,
+    remote: &RemoteModel,
+    file_path: &Path,
+    connections: u32,
+) -> Result<Option<ParallelOutcome>, String> {
+    let total_size = remote.total_size;
+    let sidecar_path = ranges_path(file_path);
+    let resumed = load_sidecar(&sidecar_path, total_size);
+    if resumed.is_none() && sidecar_path.exists() {
+        // Left by a download of a different file; its data is no use here.
+        let _ = std::fs::remove_file(file_path);
+        let _ = std::fs::remove_file(&sidecar_path);
+    }
+    let prefix = file_path.metadata().map(|m| m.len()).unwrap_or(0);
+    if resumed.is_none() && (connections <= 1 || prefix >= total_size) {
+        return Ok(None);
+    }
+
+    let url = if supports_ranges(client, remote.resolved_url.as_str()).await {
+        remote.resolved_url.to_string()
+    } else if supports_ranges(client, &remote.url).await {
+        remote.url.clone()
+    } else {
+        tracing::info!("Server does not support range requests, using a single connection");
+        if resumed.is_some() {
+            let _ = std::fs::remove_file(file_path);
+            let _ = std::fs::remove_file(&sidecar_path);
+        }
+        return Ok(None);
+    };
+
+    let segments = resumed.unwrap_or_else(|| plan_segments(total_size, prefix, connections));
+    let queue: VecDeque<usize> = segments
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| !s.is_complete())
+        .map(|(i, _)| i)
+        .collect();
+    let pending: u64 = segments.iter().map(|s| s.end - s.start - s.done).sum();
+    let workers = (connections as usize).min(queue.len());
+    tracing::info!(
+        "Downloading {} segments over {} connections",
+        queue.len(),
+        workers
+    );
+
+    let file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(file_path)
+        .map_err(|e| e.to_string())?;
+    save_sidecar(&sidecar_path, total_size, &segments);
+    let shared = Shared {
+        segments: Mutex::new(segments),
+        queue: Mutex::new(queue),
+        downloaded: AtomicU64::new(total_size - pending),
+        failures: AtomicU32::new(0),
+        failed: AtomicBool::new(false),
+        throttle: Mutex::new(Throttle::new(
+            crate::get_config_internal(app)
+                .ok()
+                .and_then(|c| c.download_max_bytes_per_sec),
+        )),
+    };
+    crate::emit_progress(app, shared.downloaded.load(Ordering::SeqCst), total_size)?;
+
+    let snapshot = |shared: &Shared| {
+        if let Ok(segments) = shared.segments.lock() {
+            save_sidecar(&sidecar_path, total_size, &segments);
+        }
+    };
+    let prefix_of = |shared: &Shared| {
+        shared
+            .segments
+            .lock()
+            .map(|segments| finished_prefix(&segments, total_size))
+            .unwrap_or(0)
+    };
+    let mut trace = DownloadTrace::start_if_enabled(app, shared.downloaded.load(Ordering::SeqCst));
+    // The hasher is either here, with `hashed` bytes fed in, or out on a
+    // blocking thread catching up to the finished prefix.
+    let mut hasher = Some(Sha256::new());
+    let mut hashed = 0u64;
+    let mut hashing = None;
+    let all = futures_util::future::join_all(
+        (0..workers).map(|worker| run_worker(app, client, &url, &file, &shared, worker)),
+    );
+    tokio::pin!(all);
+    let mut tick = tokio::time::interval(PROGRESS_INTERVAL);
+    let results = loop {
+        tokio::select! {
+            results = &mut all => break results,
+            advanced = async { hashing.as_mut().unwrap().await }, if hashing.is_some() => {
+                let (advanced, to) = advanced;
+                hasher = Some(advanced?);
+                hashed = to;
+                hashing = None;
+            }
+            _ = tick.tick() => {
+                let downloaded = shared.downloaded.load(Ordering::SeqCst);
+                crate::emit_progress(app, downloaded, total_size)?;
+                if let Some(trace) = trace.as_mut() {
+                    trace.record(downloaded);
+                }
+                snapshot(&shared);
+                let prefix = prefix_of(&shared);
+                if prefix > hashed {
+                    if let Some(idle) = hasher.take() {
+                        hashing = Some(Box::pin(async move {
+                            (advance_hash(file_path, idle, hashed, prefix).await, prefix)
+                        }));
+                    }
+                }
+            }
+        }
+    };
+    file.sync_all().map_err(|e| e.to_string())?;
+    snapshot(&shared);
+    if let Some(e) = results.into_iter().find_map(Result::err) {
+        return Err(e);
+    }
+
+    let downloaded = shared.downloaded.load(Ordering::SeqCst);
+    let complete = shared
+        .segments
+        .lock()
+        .map(|segments| segments.iter().all(Segment::is_complete))
+        .unwrap_or(false);
+    let control = app.state::<DownloadControl>();
+    if complete {
+        let _ = std::fs::remove_file(&sidecar_path);
+        let (hasher, hashed) = match hashing {
+            Some(pending) => {
+                let (advanced, to) = pending.await;
+                (advanced?, to)
+            }
+            None => (hasher.unwrap_or_default(), hashed),
+        };
+        let hasher = advance_hash(file_path, hasher, hashed, total_size).await?;
+        let digest = hex::encode(hasher.finalize());
+        Ok(Some(ParallelOutcome::Completed(digest)))
+    } else if control.pause.load(Ordering::SeqCst) {
+        Ok(Some(ParallelOutcome::Paused(downloaded)))
+    } else if control.cancel.load(Ordering::SeqCst) {
+        Ok(Some(ParallelOutcome::Cancelled(downloaded)))
+    } else {
+        Err("Parallel download stopped before finishing".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn test_plan_segments() {
+        let segments = plan_segments(100, 40, 2);
+        assert_eq!(segments.first().unwrap().start, 40);
+        assert_eq!(segments.last().unwrap().end, 100);
+        assert!(segments.windows(2).all(|w| w[0].end == w[1].start));
+        assert!(segments.iter().all(|s| s.end - s.start <= 13));
+        assert!(plan_segments(100, 100, 4).is_empty());
+    }
+
+    #[test]
+    fn test_finished_prefix() {
+        let mut segments = plan_segments(100, 40, 2);
+        assert_eq!(finished_prefix(&segments, 100), 40);
+        segments[1].done = segments[1].end - segments[1].start;
+        assert_eq!(finished_prefix(&segments, 100), 40);
+        segments[0].done = 5;
+        assert_eq!(finished_prefix(&segments, 100), 45);
+        segments[0].done = segments[0].end - segments[0].start;
+        assert_eq!(finished_prefix(&segments, 100), segments[1].end);
+        assert_eq!(finished_prefix(&[], 100), 100);
+    }
+
+    #[test]
+    fn test_parse_content_range() {
+        assert_eq!(parse_content_range("bytes 100-199/1000"), Some((100, 199)));
+        assert_eq!(parse_content_range("bytes 0-0/*"), Some((0, 0)));
+        assert_eq!(parse_content_range("bytes */1000"), None);
+        assert_eq!(parse_content_range("items 0-9/10"), None);
+    }
+
+    /// Serves `body` honoring single `Range: bytes=a-b` requests.
+    async fn spawn_range_server(body: &'static [u8]) -> u16 {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]).to_ascii_lowercase();
+                    let range = request
+                        .lines()
+                        .find_map(|line| line.strip_prefix("range: bytes="))
+                        .and_then(|r| r.trim().split_once('-'))
+                        .map(|(a, b)| (a.parse::<usize>().unwrap(), b.parse::<usize>().unwrap()));
+                    let response = match range {
+                        Some((start, end)) => {
+                            let mut head = format!(
+                                "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/{}\r\nConnection: close\r\n\r\n",
+                                end - start + 1,
+                                start,
+                                end,
+                                body.len()
+                            )
+                            .into_bytes();
+                            head.extend_from_slice(&body[start..=end]);
+                            head
+                        }
+                        None => {
+                            let mut head = format!(
+                                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                                body.len()
+                            )
+                            .into_bytes();
+                            head.extend_from_slice(body);
+                            head
+                        }
+                    };
+                    let _ = socket.write_all(&response).await;
+                });
+            }
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn test_parallel_download_resumes_from_sidecar() {
+        const BODY: &[u8] = b"GGUF0123456789abcdefghijklmnopqrstuvwxyz";
+        let port = spawn_range_server(BODY).await;
+        let url = format!("http://127.0.0.1:{}/model", port);
+        let remote = RemoteModel {
+            url: url.clone(),
+            resolved_url: reqwest::Url::parse(&url).unwrap(),
+            total_size: BODY.len() as u64,
+            last_modified: None,
+            sha256: None,
+        };
+
+        // A previous run got the first segment and part of the second.
+        let path = std::env::temp_dir().join("openmoose-parallel-test.gguf");
+        let mut partial = BODY.to_vec();
+        partial[14..].fill(0);
+        std::fs::write(&path, &partial).unwrap();
+        let mut segments = plan_segments(BODY.len() as u64, 0, 2);
+        segments[0].done = segments[0].end;
+        segments[1].done = 4;
+        save_sidecar(&ranges_path(&path), BODY.len() as u64, &segments);
+
+        let app = tauri::test::mock_app();
+        app.manage(crate::DownloadState(Mutex::new(None)));
+        app.manage(DownloadControl::default());
+        let client = ModelClient::new(crate::build_http_client().unwrap(), None);
+        let outcome = download(app.handle(), &client, &remote, &path, 3)
+            .await
+            .unwrap();
+
+        let digest = hex::encode(Sha256::digest(BODY));
+        assert!(matches!(outcome, Some(ParallelOutcome::Completed(d)) if d == digest));
+        assert_eq!(std::fs::read(&path).unwrap(), BODY);
+        assert!(!ranges_path(&path).exists());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_parallel_download_continues_after_prefix() {
+        const BODY: &[u8] = b"GGUF0123456789abcdefghijklmnopqrstuvwxyz";
+        let port = spawn_range_server(BODY).await;
+        let url = format!("http://127.0.0.1:{}/model", port);
+        let remote = RemoteModel {
+            url: url.clone(),
+            resolved_url: reqwest::Url::parse(&url).unwrap(),
+            total_size: BODY.len() as u64,
+            last_modified: None,
+            sha256: None,
+        };
+
+        // A single-stream attempt already got the first bytes.
+        let path = std::env::temp_dir().join("openmoose-parallel-prefix-test.gguf");
+        std::fs::write(&path, &BODY[..10]).unwrap();
+
+        let app = tauri::test::mock_app();
+        app.manage(crate::DownloadState(Mutex::new(None)));
+        app.manage(DownloadControl::default());
+        let client = ModelClient::new(crate::build_http_client().unwrap(), None);
+        let outcome = download(app.handle(), &client, &remote, &path, 3)
+            .await
+            .unwrap();
+
+        let digest = hex::encode(Sha256::digest(BODY));
+        assert!(matches!(outcome, Some(ParallelOutcome::Completed(d)) if d == digest));
+        assert_eq!(std::fs::read(&path).unwrap(), BODY);
+        assert!(!ranges_path(&path).exists());
+        let _ = std::fs::remove_file(&path);
+    }
+}
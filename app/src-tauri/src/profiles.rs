@@ -0,0 +1,221 @@
+//! Named gateway configurations ("profiles") stored in config.json.
+//!
+//! The flat top-level config is the implicit "default" profile, so configs
+//! written before profiles existed keep working unchanged.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::{AppConfig, GatewayState};
+
+pub(crate) const DEFAULT_PROFILE: &str = "default";
+
+/// Overrides a profile applies on top of the flat config when active.
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub(crate) struct ProfileConfig {
+    /// GGUF filename under `models/llama-cpp/` for the gateway to load.
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub gateway_port: Option<u16>,
+    /// LLM backend, passed to the gateway as `LLM_PROVIDER`.
+    #[serde(default)]
+    pub backend: Option<String>,
+    /// Extra environment for the gateway process.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct ProfileInfo {
+    pub name: String,
+    pub active: bool,
+    pub profile: ProfileConfig,
+}
+
+/// The active profile's overrides, or `None` when running the default profile.
+pub(crate) fn active_profile(config: &AppConfig) -> Option<&ProfileConfig> {
+    let name = config.active_profile.as_deref()?;
+    if name == DEFAULT_PROFILE {
+        return None;
+    }
+    config.profiles.get(name)
+}
+
+/// Merges a profile's model, backend and env overrides into the gateway env.
+pub(crate) fn apply_profile_env(
+    app: &tauri::AppHandle,
+    profile: &ProfileConfig,
+    env: &mut BTreeMap<String, String>,
+) {
+    if let Some(model) = &profile.model {
+        if let Ok(moose_dir) = crate::get_moose_dir(app) {
+            let model_path = moose_dir.join("models/llama-cpp").join(model);
+            env.insert(
+                "LLAMA_CPP_MODEL_PATH".to_string(),
+                model_path.to_string_lossy().to_string(),
+            );
+        }
+    }
+    if let Some(backend) = &profile.backend {
+        env.insert("LLM_PROVIDER".to_string(), backend.clone());
+    }
+    env.extend(profile.env.clone());
+}
+
+/// Reads config.json and returns it alongside its `profiles` map, creating
+/// an empty map if the key is missing.
+fn read_profiles(
+    app: &tauri::AppHandle,
+) -> Result<
+    (
+        serde_json::Value,
+        serde_json::Map<String, serde_json::Value>,
+    ),
+    String,
+> {
+    let raw = crate::read_config_raw(app)?;
+    let profiles = match raw.get("profiles") {
+        Some(serde_json::Value::Object(map)) => map.clone(),
+        Some(_) => return Err("config.json \"profiles\" is not a JSON object".to_string()),
+        None => serde_json::Map::new(),
+    };
+    Ok((raw, profiles))
+}
+
+fn write_profiles(
+    app: &tauri::AppHandle,
+    mut raw: serde_json::Value,
+    profiles: serde_json::Map<String, serde_json::Value>,
+    active: Option<&str>,
+) -> Result<(), String> {
+    let obj = raw
+        .as_object_mut()
+        .ok_or("config.json is not a JSON object")?;
+    obj.insert("profiles".to_string(), serde_json::Value::Object(profiles));
+    if let Some(active) = active {
+        obj.insert(
+            "active_profile".to_string(),
+            serde_json::Value::String(active.to_string()),
+        );
+    }
+    crate::write_config_raw(app, &raw)
+}
+
+/// Restarts the gateway if it's running so it picks up the new profile.
+async fn restart_if_running(
+    app: &tauri::AppHandle,
+    state: &State<'_, GatewayState>,
+) -> Result<(), String> {
+    crate::restart_gateway_internal(app, state, true)
+        .await
+        .map(|_| ())
+}
+
+#[tauri::command]
+pub(crate) async fn list_profiles(app: tauri::AppHandle) -> Result<Vec<ProfileInfo>, AppError> {
+    let config = crate::get_config_internal(&app).map_err(AppError::Config)?;
+    let active = config
+        .active_profile
+        .clone()
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+
+    let mut profiles = vec![ProfileInfo {
+        name: DEFAULT_PROFILE.to_string(),
+        active: active == DEFAULT_PROFILE,
+        profile: ProfileConfig::default(),
+    }];
+    let mut named: Vec<_> = config.profiles.into_iter().collect();
+    named.sort_by(|a, b| a.0.cmp(&b.0));
+    profiles.extend(named.into_iter().map(|(name, profile)| ProfileInfo {
+        active: name == active,
+        name,
+        profile,
+    }));
+    Ok(profiles)
+}
+
+#[tauri::command]
+pub(crate) async fn create_profile(
+    app: tauri::AppHandle,
+    name: String,
+    profile: ProfileConfig,
+) -> Result<(), AppError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::Config("Profile name cannot be empty".to_string()));
+    }
+    if name == DEFAULT_PROFILE {
+        return Err(AppError::Config(format!(
+            "\"{}\" is reserved for the base config",
+            DEFAULT_PROFILE
+        )));
+    }
+
+    let (raw, mut profiles) = read_profiles(&app).map_err(AppError::Config)?;
+    if profiles.contains_key(&name) {
+        return Err(AppError::Config(format!(
+            "Profile \"{}\" already exists",
+            name
+        )));
+    }
+    let value = serde_json::to_value(&profile)?;
+    profiles.insert(name, value);
+    write_profiles(&app, raw, profiles, None).map_err(AppError::Config)
+}
+
+/// Makes `name` the active profile and restarts the gateway to apply it.
+#[tauri::command]
+pub(crate) async fn switch_profile(
+    app: tauri::AppHandle,
+    state: State<'_, GatewayState>,
+    name: String,
+) -> Result<(), AppError> {
+    let (raw, profiles) = read_profiles(&app).map_err(AppError::Config)?;
+    if name != DEFAULT_PROFILE && !profiles.contains_key(&name) {
+        return Err(AppError::NotFound(format!(
+            "Profile \"{}\" does not exist",
+            name
+        )));
+    }
+    crate::backup::backup_config_internal(&app).map_err(AppError::Io)?;
+    write_profiles(&app, raw, profiles, Some(&name)).map_err(AppError::Config)?;
+    tracing::info!("Switched to profile \"{}\"", name);
+    restart_if_running(&app, &state)
+        .await
+        .map_err(AppError::Gateway)
+}
+
+/// Deletes a named profile. Deleting the active one falls back to the
+/// default profile.
+#[tauri::command]
+pub(crate) async fn delete_profile(
+    app: tauri::AppHandle,
+    state: State<'_, GatewayState>,
+    name: String,
+) -> Result<(), AppError> {
+    if name == DEFAULT_PROFILE {
+        return Err(AppError::Config(
+            "The default profile cannot be deleted".to_string(),
+        ));
+    }
+    let (raw, mut profiles) = read_profiles(&app).map_err(AppError::Config)?;
+    if profiles.remove(&name).is_none() {
+        return Err(AppError::NotFound(format!(
+            "Profile \"{}\" does not exist",
+            name
+        )));
+    }
+
+    let was_active = raw.get("active_profile").and_then(|v| v.as_str()) == Some(name.as_str());
+    if was_active {
+        write_profiles(&app, raw, profiles, Some(DEFAULT_PROFILE)).map_err(AppError::Config)?;
+        restart_if_running(&app, &state)
+            .await
+            .map_err(AppError::Gateway)
+    } else {
+        write_profiles(&app, raw, profiles, None).map_err(AppError::Config)
+    }
+}
@@ -0,0 +1,147 @@
+//! Deferred model downloads (`schedule_download`), persisted across restarts.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use crate::error::AppError;
+
+const SCHEDULE_FILENAME: &str = "download-schedule.json";
+// Re-check the wall clock at least this often so a suspended laptop still
+// starts the download on time after waking.
+const SCHEDULE_POLL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct DownloadSchedule {
+    pub at_epoch_secs: u64,
+}
+
+/// The armed timer task, if a download is scheduled.
+#[derive(Default)]
+pub(crate) struct ScheduleState(Mutex<Option<tauri::async_runtime::JoinHandle<()>>>);
+
+fn schedule_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    Ok(crate::get_moose_dir(app)?.join(SCHEDULE_FILENAME))
+}
+
+fn now_epoch_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Spawns the timer task, replacing (and aborting) any previously armed one.
+/// Once it fires the download runs in a task of its own, so the stored handle
+/// only ever stands for a pending timer and cancelling can't cut a running
+/// download short.
+fn arm<R: Runtime>(app: &AppHandle<R>, schedule: DownloadSchedule) {
+    let handle = app.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        loop {
+            let now = now_epoch_secs();
+            if now >= schedule.at_epoch_secs {
+                break;
+            }
+            let wait = Duration::from_secs(schedule.at_epoch_secs - now).min(SCHEDULE_POLL);
+            tokio::time::sleep(wait).await;
+        }
+
+        if let Ok(mut armed) = handle.state::<ScheduleState>().0.lock() {
+            // A newer schedule replaced this one just as it fired.
+            if armed
+                .as_ref()
+                .is_none_or(|task| task.inner().id() != tokio::task::id())
+            {
+                return;
+            }
+            armed.take();
+        }
+        if let Ok(path) = schedule_path(&handle) {
+            let _ = std::fs::remove_file(path);
+        }
+        tracing::info!("Starting scheduled model download");
+        let _ = handle.emit("download-starting-scheduled", &schedule);
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = crate::download_model(handle, None).await {
+                tracing::warn!("Scheduled download failed: {}", e);
+            }
+        });
+    });
+
+    if let Ok(mut armed) = app.state::<ScheduleState>().0.lock() {
+        if let Some(previous) = armed.replace(task) {
+            previous.abort();
+        }
+    }
+}
+
+/// Re-arms a schedule persisted by a previous run. A time that already passed
+/// while the app was closed starts the download right away.
+pub(crate) fn restore_schedule<R: Runtime>(app: &AppHandle<R>) {
+    let Ok(path) = schedule_path(app) else {
+        return;
+    };
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return;
+    };
+    match serde_json::from_str::<DownloadSchedule>(&content) {
+        Ok(schedule) => {
+            tracing::info!(
+                "Restoring download scheduled for {}",
+                schedule.at_epoch_secs
+            );
+            arm(app, schedule);
+        }
+        Err(e) => {
+            tracing::warn!("Ignoring unreadable download schedule: {}", e);
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Starts the model download at `at_epoch_secs` (Unix time), replacing any
+/// existing schedule.
+#[tauri::command]
+pub(crate) async fn schedule_download(
+    app: tauri::AppHandle,
+    at_epoch_secs: u64,
+) -> Result<DownloadSchedule, AppError> {
+    let schedule = DownloadSchedule { at_epoch_secs };
+    let path = schedule_path(&app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string_pretty(&schedule)?;
+    std::fs::write(&path, content)?;
+
+    arm(&app, schedule.clone());
+    app.emit("download-scheduled", &schedule)?;
+    Ok(schedule)
+}
+
+/// Removes a pending schedule. Returns whether one was armed.
+#[tauri::command]
+pub(crate) async fn cancel_scheduled_download(
+    app: tauri::AppHandle,
+    state: State<'_, ScheduleState>,
+) -> Result<bool, AppError> {
+    let task = state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to acquire schedule lock: {}", e))?
+        .take();
+    let path = schedule_path(&app)?;
+    if path.exists() {
+        std::fs::remove_file(&path)?;
+    }
+    match task {
+        Some(task) => {
+            task.abort();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
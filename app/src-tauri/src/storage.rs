@@ -0,0 +1,489 @@
+//! Filesystem checks for the volume that holds the model, and the list of
+//! volumes it could be moved to.
+//!
+//! Network mounts are identified before asking for their capacity: `statvfs`
+//! on a hard-mounted NFS/CIFS share whose server is gone blocks indefinitely.
+
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::error::AppError;
+
+/// Amount of data written by `test_model_volume_integrity`.
+const VOLUME_TEST_SIZE: u64 = 256 * 1024 * 1024;
+const VOLUME_TEST_CHUNK: usize = 1024 * 1024;
+const VOLUME_TEST_FILENAME: &str = ".moose-volume-test.tmp";
+
+/// Filesystems that never make sense as a model location.
+const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "autofs",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devfs",
+    "devpts",
+    "devtmpfs",
+    "efivarfs",
+    "fusectl",
+    "hugetlbfs",
+    "mqueue",
+    "nsfs",
+    "overlay",
+    "proc",
+    "pstore",
+    "ramfs",
+    "securityfs",
+    "squashfs",
+    "sysfs",
+    "tmpfs",
+    "tracefs",
+];
+/// Filesystems served over the network, where loading a multi-GB model crawls.
+const NETWORK_FILESYSTEMS: &[&str] = &[
+    "9p",
+    "afpfs",
+    "afs",
+    "ceph",
+    "cifs",
+    "davfs",
+    "fuse.rclone",
+    "fuse.sshfs",
+    "glusterfs",
+    "ncpfs",
+    "nfs",
+    "nfs4",
+    "smb",
+    "smb2",
+    "smb3",
+    "smbfs",
+    "sshfs",
+    "webdav",
+];
+/// Path components of folders that cloud-sync clients mirror to the network.
+const CLOUD_SYNC_FOLDERS: &[&str] = &[
+    "Dropbox",
+    "OneDrive",
+    "Google Drive",
+    "My Drive",
+    "iCloud Drive",
+    "Mobile Documents",
+    "CloudStorage",
+    "Box",
+    "Box Sync",
+    "pCloud Drive",
+    "MEGA",
+];
+/// System mount trees that may be writable but aren't meant for user data.
+const SYSTEM_MOUNT_PREFIXES: &[&str] = &["/boot", "/dev", "/proc", "/run", "/snap", "/sys"];
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct VolumeInfo {
+    pub path: String,
+    pub total: u64,
+    pub free: u64,
+    pub is_removable: bool,
+}
+
+/// Capacity of one filesystem, as returned by `get_disk_space`.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct DiskSpace {
+    pub total: u64,
+    /// Available to this user, which can be less than what's unallocated.
+    pub free: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct ModelFilesystemCheck {
+    pub path: String,
+    /// Filesystem type of the mount holding the model directory, if known.
+    pub file_system: Option<String>,
+    pub mount_point: Option<String>,
+    pub is_network: bool,
+    /// The cloud-sync folder the model sits in (e.g. "Dropbox"), if any.
+    pub cloud_sync: Option<String>,
+    /// Set when the location is a poor place for the model.
+    pub warning: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct VolumeIntegrityReport {
+    pub passed: bool,
+    pub bytes_tested: u64,
+    /// Offset of the first byte that didn't read back as written.
+    pub mismatch_offset: Option<u64>,
+    pub write_mb_per_sec: f64,
+    pub read_mb_per_sec: f64,
+}
+
+/// Deterministic xorshift64 byte stream, so the read pass can regenerate
+/// exactly what was written without keeping it in memory.
+struct PatternStream(u64);
+
+impl PatternStream {
+    fn fill(&mut self, buf: &mut [u8]) {
+        for chunk in buf.chunks_mut(8) {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            chunk.copy_from_slice(&self.0.to_le_bytes()[..chunk.len()]);
+        }
+    }
+}
+
+/// Removes the test file however the check ends.
+struct RemoveOnDrop(PathBuf);
+
+impl Drop for RemoveOnDrop {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Asks the OS to evict the file from the page cache so the read pass hits
+/// the disk rather than memory. Best effort: a no-op where unsupported.
+fn drop_file_cache(file: &std::fs::File) {
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::io::AsRawFd;
+        unsafe {
+            libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED);
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = file;
+}
+
+fn mb_per_sec(bytes: u64, started: Instant) -> f64 {
+    let secs = started.elapsed().as_secs_f64().max(f64::EPSILON);
+    bytes as f64 / (1024.0 * 1024.0) / secs
+}
+
+/// Writes `size` bytes of a known pattern into `dir`, syncs, reads it back and
+/// compares byte-for-byte.
+pub(crate) fn check_volume_integrity(
+    dir: &Path,
+    size: u64,
+) -> Result<VolumeIntegrityReport, String> {
+    let path = dir.join(VOLUME_TEST_FILENAME);
+    let _cleanup = RemoveOnDrop(path.clone());
+    let seed = 0x9E37_79B9_7F4A_7C15;
+    let mut buf = vec![0u8; VOLUME_TEST_CHUNK];
+
+    let write_started = Instant::now();
+    let mut file = std::fs::File::create(&path)
+        .map_err(|e| format!("Failed to create volume test file: {}", e))?;
+    let mut pattern = PatternStream(seed);
+    let mut written = 0u64;
+    while written < size {
+        let len = (size - written).min(VOLUME_TEST_CHUNK as u64) as usize;
+        pattern.fill(&mut buf[..len]);
+        file.write_all(&buf[..len])
+            .map_err(|e| format!("Volume write failed: {}", e))?;
+        written += len as u64;
+    }
+    file.sync_all()
+        .map_err(|e| format!("Volume fsync failed: {}", e))?;
+    let write_mb_per_sec = mb_per_sec(written, write_started);
+    drop_file_cache(&file);
+    drop(file);
+
+    let read_started = Instant::now();
+    let mut file = std::fs::File::open(&path)
+        .map_err(|e| format!("Failed to reopen volume test file: {}", e))?;
+    let mut pattern = PatternStream(seed);
+    let mut expected = vec![0u8; VOLUME_TEST_CHUNK];
+    let mut offset = 0u64;
+    let mut mismatch_offset = None;
+    while offset < written {
+        let len = (written - offset).min(VOLUME_TEST_CHUNK as u64) as usize;
+        file.read_exact(&mut buf[..len])
+            .map_err(|e| format!("Volume read failed: {}", e))?;
+        pattern.fill(&mut expected[..len]);
+        if let Some(i) = (0..len).find(|&i| buf[i] != expected[i]) {
+            mismatch_offset = Some(offset + i as u64);
+            break;
+        }
+        offset += len as u64;
+    }
+
+    Ok(VolumeIntegrityReport {
+        passed: mismatch_offset.is_none(),
+        bytes_tested: written,
+        mismatch_offset,
+        write_mb_per_sec,
+        read_mb_per_sec: mb_per_sec(offset, read_started),
+    })
+}
+
+/// Writes a few hundred MB to the models directory and reads it back, to catch
+/// a drive that silently corrupts data before a multi-GB download lands on it.
+#[tauri::command]
+pub(crate) async fn test_model_volume_integrity(
+    app: tauri::AppHandle,
+) -> Result<VolumeIntegrityReport, AppError> {
+    let model_path = crate::get_model_path(&app)?;
+    let dir = model_path
+        .parent()
+        .ok_or("Model path has no parent directory")?
+        .to_path_buf();
+    std::fs::create_dir_all(&dir)?;
+
+    tokio::task::spawn_blocking(move || check_volume_integrity(&dir, VOLUME_TEST_SIZE))
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))?
+        .map_err(AppError::Io)
+}
+
+fn is_network_filesystem(file_system: &str) -> bool {
+    NETWORK_FILESYSTEMS.contains(&file_system.to_ascii_lowercase().as_str())
+}
+
+/// The cloud-sync folder `path` is inside, by folder name. OneDrive folders
+/// are often suffixed ("OneDrive - Contoso").
+fn cloud_sync_folder(path: &Path) -> Option<String> {
+    path.components().find_map(|component| {
+        let name = component.as_os_str().to_string_lossy();
+        CLOUD_SYNC_FOLDERS
+            .iter()
+            .find(|folder| {
+                name == **folder || (**folder == "OneDrive" && name.starts_with("OneDrive"))
+            })
+            .map(|folder| folder.to_string())
+    })
+}
+
+/// The path whose mount `dir` is on. The directory may not exist yet; its
+/// nearest existing ancestor is on the same mount. Symlinks are resolved on
+/// Unix only, since Windows canonicalizes to a `\\?\` path that no longer
+/// prefix-matches mount points.
+fn resolve_for_mount(dir: &Path) -> PathBuf {
+    let existing = dir.ancestors().find(|p| p.exists()).unwrap_or(dir);
+    if cfg!(windows) {
+        existing.to_path_buf()
+    } else {
+        existing
+            .canonicalize()
+            .unwrap_or_else(|_| existing.to_path_buf())
+    }
+}
+
+/// Index of the disk with the longest mount point containing `path`.
+fn mount_index(disks: &sysinfo::Disks, path: &Path) -> Option<usize> {
+    disks
+        .list()
+        .iter()
+        .enumerate()
+        .filter(|(_, disk)| path.starts_with(disk.mount_point()))
+        .max_by_key(|(_, disk)| disk.mount_point().as_os_str().len())
+        .map(|(index, _)| index)
+}
+
+/// Size and free space of the filesystem holding `path`. Only that one
+/// filesystem's capacity is queried.
+pub(crate) fn disk_space(path: &Path) -> Result<DiskSpace, String> {
+    let resolved = resolve_for_mount(path);
+    let mut disks =
+        sysinfo::Disks::new_with_refreshed_list_specifics(sysinfo::DiskRefreshKind::nothing());
+    let index = mount_index(&disks, &resolved)
+        .ok_or_else(|| format!("Could not find the filesystem holding {}", path.display()))?;
+    let disk = &mut disks.list_mut()[index];
+    disk.refresh_specifics(sysinfo::DiskRefreshKind::nothing().with_storage());
+    Ok(DiskSpace {
+        total: disk.total_space(),
+        free: disk.available_space(),
+    })
+}
+
+/// Free space available to this user on the filesystem holding `path`.
+pub(crate) fn available_space(path: &Path) -> Result<u64, String> {
+    disk_space(path).map(|space| space.free)
+}
+
+/// Capacity of the filesystem holding ~/.moose, so setup can warn before a
+/// download that won't fit. Works before ~/.moose exists.
+#[tauri::command]
+pub(crate) async fn get_disk_space(app: tauri::AppHandle) -> Result<DiskSpace, AppError> {
+    let dir = crate::get_moose_dir(&app)?;
+    tokio::task::spawn_blocking(move || disk_space(&dir))
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))?
+        .map_err(AppError::Io)
+}
+
+/// Classifies where `dir` lives without touching the filesystem's capacity
+/// (see the module docs for why).
+pub(crate) fn check_filesystem(dir: &Path) -> ModelFilesystemCheck {
+    let resolved = resolve_for_mount(dir);
+    let disks =
+        sysinfo::Disks::new_with_refreshed_list_specifics(sysinfo::DiskRefreshKind::nothing());
+    let mount = mount_index(&disks, &resolved).map(|index| &disks.list()[index]);
+
+    let file_system = mount.map(|disk| disk.file_system().to_string_lossy().to_string());
+    let resolved_str = resolved.to_string_lossy();
+    let is_unc = resolved_str.starts_with(r"\\") && !resolved_str.starts_with(r"\\?\")
+        || resolved_str.starts_with(r"\\?\UNC\");
+    let is_network = is_unc || file_system.as_deref().is_some_and(is_network_filesystem);
+    let cloud_sync = cloud_sync_folder(&resolved);
+
+    let warning = if is_network {
+        Some(
+            "The model is on a network drive; loading it will be slow and may stall the gateway."
+                .to_string(),
+        )
+    } else {
+        cloud_sync.as_ref().map(|folder| {
+            format!(
+                "The model is inside a {} folder; syncing a multi-GB file slows loading and uses a lot of bandwidth.",
+                folder
+            )
+        })
+    };
+
+    ModelFilesystemCheck {
+        path: dir.to_string_lossy().to_string(),
+        file_system,
+        mount_point: mount.map(|disk| disk.mount_point().to_string_lossy().to_string()),
+        is_network,
+        cloud_sync,
+        warning,
+    }
+}
+
+/// Warns when the model directory is on a network filesystem or inside a
+/// cloud-synced folder.
+#[tauri::command]
+pub(crate) async fn check_model_filesystem(
+    app: tauri::AppHandle,
+) -> Result<ModelFilesystemCheck, AppError> {
+    let model_path = crate::get_model_path(&app)?;
+    let dir = model_path
+        .parent()
+        .ok_or("Model path has no parent directory")?
+        .to_path_buf();
+    tokio::task::spawn_blocking(move || check_filesystem(&dir))
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))
+}
+
+/// Whether a mount is a real, user-facing volume worth offering for the model.
+fn is_candidate_volume(mount_point: &Path, file_system: &str, total: u64) -> bool {
+    if total == 0 || PSEUDO_FILESYSTEMS.contains(&file_system.to_ascii_lowercase().as_str()) {
+        return false;
+    }
+    // macOS exposes the system volume's internals under /System/Volumes; only
+    // the Data volume holds user files.
+    if mount_point.starts_with("/System/Volumes") {
+        return mount_point == Path::new("/System/Volumes/Data");
+    }
+    !SYSTEM_MOUNT_PREFIXES
+        .iter()
+        .any(|prefix| mount_point.starts_with(prefix))
+}
+
+/// Lists mounted, writable volumes with their capacity, so the UI can offer
+/// somewhere with room for the model.
+#[tauri::command]
+pub(crate) async fn list_volumes() -> Result<Vec<VolumeInfo>, AppError> {
+    tokio::task::spawn_blocking(|| {
+        let mut disks =
+            sysinfo::Disks::new_with_refreshed_list_specifics(sysinfo::DiskRefreshKind::nothing());
+        let mut volumes: Vec<VolumeInfo> = Vec::new();
+        for disk in disks.list_mut() {
+            if is_network_filesystem(&disk.file_system().to_string_lossy()) {
+                continue;
+            }
+            disk.refresh_specifics(sysinfo::DiskRefreshKind::nothing().with_storage());
+            let mount_point = disk.mount_point();
+            if disk.is_read_only()
+                || !is_candidate_volume(
+                    mount_point,
+                    &disk.file_system().to_string_lossy(),
+                    disk.total_space(),
+                )
+            {
+                continue;
+            }
+            let path = mount_point.to_string_lossy().to_string();
+            // Bind mounts show the same volume more than once.
+            if volumes.iter().any(|v| v.path == path) {
+                continue;
+            }
+            volumes.push(VolumeInfo {
+                path,
+                total: disk.total_space(),
+                free: disk.available_space(),
+                is_removable: disk.is_removable(),
+            });
+        }
+        volumes.sort_by_key(|v| std::cmp::Reverse(v.free));
+        volumes
+    })
+    .await
+    .map_err(|e| AppError::Other(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_volume_integrity_round_trip() {
+        let dir = std::env::temp_dir().join("openmoose-volume-test");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let report = check_volume_integrity(&dir, 3 * 1024 * 1024 + 5).unwrap();
+        assert!(report.passed);
+        assert_eq!(report.bytes_tested, 3 * 1024 * 1024 + 5);
+        assert!(!dir.join(VOLUME_TEST_FILENAME).exists());
+    }
+
+    #[test]
+    fn test_disk_space_of_missing_dir() {
+        // Resolved through the nearest existing ancestor.
+        let dir = std::env::temp_dir().join("openmoose-disk-space-test/not/yet");
+        let space = disk_space(&dir).unwrap();
+        assert!(space.total > 0);
+        assert!(space.free <= space.total);
+    }
+
+    #[test]
+    fn test_cloud_sync_folder() {
+        assert_eq!(
+            cloud_sync_folder(Path::new("/Users/me/Dropbox/models")),
+            Some("Dropbox".to_string())
+        );
+        assert_eq!(
+            cloud_sync_folder(Path::new("/Users/me/OneDrive - Contoso/.moose")),
+            Some("OneDrive".to_string())
+        );
+        assert_eq!(cloud_sync_folder(Path::new("/home/me/.moose/models")), None);
+        assert!(is_network_filesystem("NFS4"));
+        assert!(!is_network_filesystem("ext4"));
+    }
+
+    #[test]
+    fn test_is_candidate_volume_filters_pseudo_mounts() {
+        assert!(is_candidate_volume(Path::new("/"), "ext4", 100));
+        assert!(is_candidate_volume(Path::new("/media/usb"), "vfat", 100));
+        assert!(is_candidate_volume(
+            Path::new("/System/Volumes/Data"),
+            "apfs",
+            100
+        ));
+        assert!(!is_candidate_volume(
+            Path::new("/System/Volumes/VM"),
+            "apfs",
+            100
+        ));
+        assert!(!is_candidate_volume(
+            Path::new("/run/user/1000"),
+            "ext4",
+            100
+        ));
+        assert!(!is_candidate_volume(Path::new("/tmp"), "tmpfs", 100));
+        assert!(!is_candidate_volume(Path::new("/mnt/empty"), "ext4", 0));
+    }
+}
@@ -0,0 +1,167 @@
+//! Disk usage of the app's files by category (`get_storage_usage`), for the
+//! Storage settings panel, so users can see what is worth deleting.
+//!
+//! Symlinks are followed, since a models folder linked to another drive should
+//! count, so the walk is bounded in depth and entries: a link back to a parent
+//! directory ends in a truncated total instead of a hang.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::error::AppError;
+
+const MAX_WALK_DEPTH: usize = 32;
+const MAX_WALK_ENTRIES: usize = 200_000;
+/// Sidecars of an unfinished download, next to the model's final name.
+const PARTIAL_SUFFIXES: &[&str] = &[".part", ".meta", ".ranges"];
+
+/// Bytes used, by category.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub(crate) struct StorageUsage {
+    /// Downloaded models, every model under models/ and not only the active one.
+    pub models: u64,
+    /// Unfinished downloads: .part files and their sidecars.
+    pub partial_downloads: u64,
+    pub logs: u64,
+    /// config.json, its .bak and backups.
+    pub config: u64,
+    /// Everything else: the gateway's memory and history, caches.
+    pub other: u64,
+    pub total: u64,
+    /// The walk hit its bound (likely a symlink loop), so the figures are a
+    /// lower bound.
+    pub truncated: bool,
+}
+
+/// Calls `visit` with the path and size of every file under `root`, until
+/// `budget` entries have been looked at. Returns false if a bound was hit.
+fn walk(root: &Path, budget: &mut usize, visit: &mut impl FnMut(&Path, u64)) -> bool {
+    let mut stack = vec![(root.to_path_buf(), 0)];
+    let mut complete = true;
+    while let Some((path, depth)) = stack.pop() {
+        if *budget == 0 {
+            return false;
+        }
+        *budget -= 1;
+        let Ok(meta) = std::fs::metadata(&path) else {
+            continue;
+        };
+        if meta.is_file() {
+            visit(&path, meta.len());
+        } else if meta.is_dir() {
+            if depth == MAX_WALK_DEPTH {
+                complete = false;
+                continue;
+            }
+            if let Ok(entries) = std::fs::read_dir(&path) {
+                stack.extend(entries.flatten().map(|entry| (entry.path(), depth + 1)));
+            }
+        }
+    }
+    complete
+}
+
+fn is_partial(path: &Path) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    PARTIAL_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+}
+
+/// Usage of `data_dir` plus, when it's elsewhere, the config files in
+/// `config_dir`.
+fn measure(data_dir: &Path, config_dir: &Path) -> StorageUsage {
+    let mut usage = StorageUsage::default();
+    let mut budget = MAX_WALK_ENTRIES;
+    let entries = std::fs::read_dir(data_dir)
+        .map(|entries| entries.flatten().collect::<Vec<_>>())
+        .unwrap_or_default();
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let complete = walk(&entry.path(), &mut budget, &mut |path, size| {
+            let bucket = match name.as_str() {
+                "models" if is_partial(path) => &mut usage.partial_downloads,
+                "models" => &mut usage.models,
+                "logs" => &mut usage.logs,
+                name if crate::CONFIG_DIR_ENTRIES.contains(&name) => &mut usage.config,
+                _ => &mut usage.other,
+            };
+            *bucket += size;
+        });
+        usage.truncated |= !complete;
+    }
+    if config_dir != data_dir {
+        for name in crate::CONFIG_DIR_ENTRIES {
+            let complete = walk(&config_dir.join(name), &mut budget, &mut |_, size| {
+                usage.config += size
+            });
+            usage.truncated |= !complete;
+        }
+    }
+    usage.total = usage.models + usage.partial_downloads + usage.logs + usage.config + usage.other;
+    usage
+}
+
+/// How much space the app's files take, by category. Zero everywhere before
+/// anything has been written.
+#[tauri::command]
+pub(crate) async fn get_storage_usage(app: tauri::AppHandle) -> Result<StorageUsage, AppError> {
+    let data_dir = crate::long_path(&crate::moose_dir_path(&app)?);
+    let config_dir = crate::long_path(&crate::get_config_dir(&app)?);
+    tokio::task::spawn_blocking(move || measure(&data_dir, &config_dir))
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_measure_by_category() {
+        let root = std::env::temp_dir().join("openmoose-storage-usage-test");
+        let _ = std::fs::remove_dir_all(&root);
+        let data = root.join("data");
+        let config = root.join("config");
+        let write = |path: PathBuf, len: usize| {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, vec![0u8; len]).unwrap();
+        };
+        write(data.join("models/llama-cpp/a.gguf"), 100);
+        write(data.join("models/audio/onnx/tts.onnx"), 50);
+        write(data.join("models/llama-cpp/b.gguf.part"), 30);
+        write(data.join("models/llama-cpp/b.gguf.meta"), 2);
+        write(data.join("logs/app.log"), 7);
+        write(data.join("memory/index"), 11);
+        write(config.join("config.json"), 5);
+        write(config.join("backups/config-1.json"), 4);
+        // Not read from the config directory.
+        write(config.join("stray"), 1000);
+
+        let usage = measure(&data, &config);
+        assert_eq!(
+            usage,
+            StorageUsage {
+                models: 150,
+                partial_downloads: 32,
+                logs: 7,
+                config: 9,
+                other: 11,
+                total: 209,
+                truncated: false,
+            }
+        );
+        assert_eq!(
+            measure(&root.join("missing"), &root.join("missing")).total,
+            0
+        );
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&data, data.join("memory/loop")).unwrap();
+            let usage = measure(&data, &config);
+            assert!(usage.truncated);
+            assert_eq!(usage.models, 150);
+        }
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,134 @@
+//! Opt-in anonymous usage events (`emit_telemetry`), to see where onboarding
+//! breaks.
+//!
+//! Nothing is sent unless `telemetry_enabled` is set and a
+//! `telemetry_endpoint` is configured; with either missing no request is ever
+//! made. Events carry the event name, the app version and platform, and
+//! props that `sanitize_props` has reduced to plain values: no file paths,
+//! URLs or anything else that could identify the user.
+
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+
+const TELEMETRY_TIMEOUT: Duration = Duration::from_secs(5);
+/// Longest string prop that is sent; anything longer is dropped.
+const MAX_PROP_LEN: usize = 64;
+
+#[derive(Serialize, Debug)]
+struct TelemetryEvent<'a> {
+    event: &'a str,
+    props: serde_json::Map<String, serde_json::Value>,
+    app_version: &'static str,
+    os: &'static str,
+    arch: &'static str,
+}
+
+/// Keeps only short, flat values: booleans, numbers, and strings that don't
+/// look like a path, URL or email address. Nested values are dropped.
+fn sanitize_props(props: serde_json::Value) -> serde_json::Map<String, serde_json::Value> {
+    let serde_json::Value::Object(props) = props else {
+        return serde_json::Map::new();
+    };
+    props
+        .into_iter()
+        .filter(|(_, value)| match value {
+            serde_json::Value::Bool(_) | serde_json::Value::Number(_) => true,
+            serde_json::Value::String(text) => {
+                text.len() <= MAX_PROP_LEN
+                    && !text.contains(['/', '\\', '@', ':'])
+                    && !text.starts_with('~')
+            }
+            _ => false,
+        })
+        .collect()
+}
+
+/// The endpoint to post to, or `None` when telemetry is off.
+fn telemetry_endpoint<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    let config = crate::get_config_internal(app).ok()?;
+    if !config.telemetry_enabled {
+        return None;
+    }
+    config
+        .telemetry_endpoint
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+}
+
+/// Sends `event` in the background if the user opted in. Never fails and
+/// never blocks the caller; a failed post is only logged.
+pub(crate) fn emit_telemetry<R: Runtime>(
+    app: &AppHandle<R>,
+    event: &str,
+    props: serde_json::Value,
+) {
+    let Some(endpoint) = telemetry_endpoint(app) else {
+        return;
+    };
+    let payload = TelemetryEvent {
+        event,
+        props: sanitize_props(props),
+        app_version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+    };
+    let Ok(body) = serde_json::to_vec(&payload) else {
+        return;
+    };
+    let client = crate::download_client_builder(app).and_then(|builder| {
+        builder
+            .timeout(TELEMETRY_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())
+    });
+    let client = match client {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("Telemetry disabled: {}", e);
+            return;
+        }
+    };
+    let event = event.to_string();
+    tauri::async_runtime::spawn(async move {
+        let sent = client
+            .post(&endpoint)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        if let Err(e) = sent {
+            tracing::warn!(
+                "Telemetry event {} not sent: {}",
+                event,
+                crate::describe_request_error(&e)
+            );
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_props_drops_identifying_values() {
+        let props = sanitize_props(serde_json::json!({
+            "model": "ministral-3-14b-reasoning",
+            "resumed": true,
+            "restart_count": 2,
+            "path": "/home/alice/.moose/models/model.gguf",
+            "windows_path": "C:\\Users\\alice",
+            "home": "~/.moose",
+            "url": "https://mirror.corp/model.gguf",
+            "email": "alice@example.com",
+            "nested": { "model": "x" },
+            "long": "x".repeat(MAX_PROP_LEN + 1),
+        }));
+        let mut keys: Vec<&str> = props.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["model", "restart_count", "resumed"]);
+        assert!(sanitize_props(serde_json::json!("not an object")).is_empty());
+    }
+}
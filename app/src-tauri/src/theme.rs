@@ -0,0 +1,156 @@
+//! The themes the app supports, and a check that the configured theme
+//! actually ships with the frontend.
+//!
+//! Built-in themes (`Theme`) live in the main stylesheet. `update_config`
+//! accepts only those; any other theme, from a hand-edited config.json, is
+//! expected at `themes/<name>.css` in the frontend dist (i.e.
+//! `app/public/themes/`).
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::error::AppError;
+
+/// Used when the configured theme's stylesheet is missing.
+const FALLBACK_THEME: &str = "dark";
+
+/// Themes styled by the main stylesheet, so they never need extra assets.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Theme {
+    Dark,
+    Light,
+    /// Follows the OS appearance.
+    System,
+}
+
+impl Theme {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+            Theme::System => "system",
+        }
+    }
+}
+
+impl std::str::FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "dark" => Ok(Theme::Dark),
+            "light" => Ok(Theme::Light),
+            "system" => Ok(Theme::System),
+            _ => Err(format!("Invalid theme '{}': use dark, light or system", s)),
+        }
+    }
+}
+
+/// The theme the frontend should render: "system" becomes the OS appearance
+/// (dark if it's unknown), anything else is passed through.
+pub(crate) fn resolve_theme(theme: &str, os_theme: Option<tauri::Theme>) -> String {
+    match theme.parse::<Theme>() {
+        Ok(Theme::System) => match os_theme {
+            Some(tauri::Theme::Light) => Theme::Light.as_str().to_string(),
+            _ => Theme::Dark.as_str().to_string(),
+        },
+        _ => theme.to_ascii_lowercase(),
+    }
+}
+
+/// The OS light/dark appearance, as seen by the app's window.
+pub(crate) fn os_theme(app: &tauri::AppHandle) -> Option<tauri::Theme> {
+    app.webview_windows()
+        .values()
+        .next()
+        .and_then(|window| window.theme().ok())
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub(crate) struct ThemeAssetCheck {
+    pub theme: String,
+    pub present: bool,
+    /// False when the frontend assets couldn't be inspected at all, in which
+    /// case `present` is assumed.
+    pub verified: bool,
+    /// The theme the UI should actually apply.
+    pub effective_theme: String,
+}
+
+fn theme_stylesheet(theme: &str) -> String {
+    format!("themes/{}.css", theme)
+}
+
+fn check_theme(theme: &str, has_asset: impl Fn(&str) -> bool) -> ThemeAssetCheck {
+    let theme = theme.to_ascii_lowercase();
+    let built_in = theme.parse::<Theme>().is_ok();
+    // Without an index.html there are no assets to check against.
+    let verified = built_in || has_asset("index.html");
+    let present = built_in || !verified || has_asset(&theme_stylesheet(&theme));
+    ThemeAssetCheck {
+        effective_theme: if present {
+            theme.clone()
+        } else {
+            FALLBACK_THEME.to_string()
+        },
+        theme,
+        present,
+        verified,
+    }
+}
+
+/// Reports whether the configured theme's stylesheet is in the frontend
+/// bundle, and which theme to fall back to if it isn't (e.g. after an update
+/// that dropped or hasn't yet shipped a theme).
+#[tauri::command]
+pub(crate) async fn validate_theme_assets(
+    app: tauri::AppHandle,
+) -> Result<ThemeAssetCheck, AppError> {
+    let config = crate::get_config_internal(&app).map_err(AppError::Config)?;
+    let resolver = app.asset_resolver();
+    let check = check_theme(&config.theme, |path| {
+        resolver.get(path.to_string()).is_some()
+    });
+    if !check.present {
+        tracing::warn!(
+            "Theme \"{}\" has no stylesheet, falling back to \"{}\"",
+            check.theme,
+            check.effective_theme
+        );
+    }
+    Ok(check)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_theme_falls_back_when_stylesheet_missing() {
+        let assets = ["index.html", "themes/solarized.css"];
+        let has = |path: &str| assets.contains(&path);
+
+        assert!(check_theme("Dark", has).present);
+        assert_eq!(check_theme("solarized", has).effective_theme, "solarized");
+
+        let missing = check_theme("neon", has);
+        assert!(!missing.present && missing.verified);
+        assert_eq!(missing.effective_theme, FALLBACK_THEME);
+
+        let unverified = check_theme("neon", |_| false);
+        assert!(unverified.present && !unverified.verified);
+    }
+
+    #[test]
+    fn test_theme_parsing_and_system_resolution() {
+        assert_eq!(" Light".parse::<Theme>(), Ok(Theme::Light));
+        assert!("solarized".parse::<Theme>().is_err());
+        assert!("".parse::<Theme>().is_err());
+
+        assert_eq!(resolve_theme("system", Some(tauri::Theme::Light)), "light");
+        assert_eq!(resolve_theme("system", Some(tauri::Theme::Dark)), "dark");
+        assert_eq!(resolve_theme("system", None), "dark");
+        assert_eq!(resolve_theme("light", Some(tauri::Theme::Dark)), "light");
+    }
+}
@@ -0,0 +1,214 @@
+//! Background supervisor for the gateway process.
+//!
+//! Notices a gateway that exited on its own (`gateway-crashed`, emitted by
+//! `reap_exited_gateway`) and starts it again with a backoff, and one that is
+//! still alive but has stopped answering health probes (`gateway-hung`).
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{AutoStartTask, GatewayState, GatewayStats};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+/// How long probes must keep failing before a live gateway counts as hung.
+const HUNG_AFTER: Duration = Duration::from_secs(60);
+/// A gateway that has never answered is only hung once it's been up this long
+/// (loading the model can take a while on first start).
+const STARTUP_GRACE: Duration = Duration::from_secs(180);
+/// Crash restarts in a row (without the gateway ever answering in between)
+/// before the watchdog gives up on a crash loop.
+const MAX_CRASH_RESTARTS: u32 = 5;
+
+/// Payload of the `gateway-hung` event.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub(crate) struct GatewayHung {
+    pub pid: u32,
+    pub uptime_secs: u64,
+    /// Unix time of the last successful health probe, if there ever was one.
+    pub last_ok_epoch_secs: Option<u64>,
+    /// Whether the watchdog is force-restarting it (`restart_hung_gateway`).
+    pub restarting: bool,
+}
+
+/// What the watchdog knows about the gateway process it's currently watching.
+struct Watched {
+    pid: u32,
+    since: Instant,
+    last_ok: Option<(Instant, SystemTime)>,
+    reported_hung: bool,
+}
+
+impl Watched {
+    fn new(pid: u32) -> Self {
+        Watched {
+            pid,
+            since: Instant::now(),
+            last_ok: None,
+            reported_hung: false,
+        }
+    }
+
+    fn is_hung(&self, now: Instant) -> bool {
+        match self.last_ok {
+            Some((at, _)) => now.duration_since(at) >= HUNG_AFTER,
+            None => now.duration_since(self.since) >= STARTUP_GRACE,
+        }
+    }
+}
+
+async fn probe_health(client: &reqwest::Client, port: u16) -> bool {
+    client
+        .get(format!("http://127.0.0.1:{}/health", port))
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .map(|res| res.status().is_success())
+        .unwrap_or(false)
+}
+
+/// Current gateway PID, reaping (and reporting) it first if it has exited.
+fn running_pid(app: &AppHandle) -> Option<u32> {
+    let state = app.state::<GatewayState>();
+    let mut lock = state.0.lock().ok()?;
+    crate::reap_exited_gateway(app, &mut lock);
+    lock.as_ref().map(|child| child.id())
+}
+
+/// Backoff before crash restart number `attempt` (from 1), or `None` once
+/// `MAX_CRASH_RESTARTS` have been spent.
+fn crash_restart_delay(attempt: u32) -> Option<Duration> {
+    (attempt <= MAX_CRASH_RESTARTS).then(|| crate::retry_backoff(attempt))
+}
+
+/// Starts a crashed gateway again after a backoff, as the pending auto-start
+/// so a manual stop or restart in the meantime cancels it. Only once setup is
+/// complete; emits `gateway-crash-loop` instead once the restarts run out.
+fn restart_crashed(app: &AppHandle, attempt: u32) {
+    let setup_complete = crate::get_config_internal(app)
+        .map(|c| c.setup_complete)
+        .unwrap_or(false);
+    if !setup_complete {
+        return;
+    }
+    let Some(delay) = crash_restart_delay(attempt) else {
+        tracing::warn!(
+            "Gateway crashed {} times in a row, not restarting it again",
+            attempt - 1
+        );
+        let _ = app.emit("gateway-crash-loop", attempt - 1);
+        return;
+    };
+    tracing::warn!(
+        "Restarting crashed gateway in {}ms (attempt {}/{})",
+        delay.as_millis(),
+        attempt,
+        MAX_CRASH_RESTARTS
+    );
+    let handle = app.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        let state = handle.state::<GatewayState>();
+        if let Err(e) = crate::start_gateway_and_wait(&handle, &state).await {
+            tracing::warn!("Failed to restart crashed gateway: {}", e);
+        }
+    });
+    app.state::<AutoStartTask>().set(task);
+}
+
+async fn restart_hung(app: &AppHandle) {
+    let state = app.state::<GatewayState>();
+    if let Err(e) = crate::restart_gateway_internal(app, &state, false).await {
+        tracing::warn!("Failed to restart hung gateway: {}", e);
+    }
+}
+
+/// Spawns the supervisor loop for the lifetime of the app.
+pub(crate) fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let client = match crate::build_http_client() {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::warn!("Gateway watchdog disabled: {}", e);
+                return;
+            }
+        };
+        let mut watched: Option<Watched> = None;
+        let stats = app.state::<GatewayStats>();
+        let mut seen_crashes = stats.crashes.load(Ordering::SeqCst);
+        let mut crash_restarts = 0;
+
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            let pid = running_pid(&app);
+            // Crashes are counted wherever the exit is first noticed; a
+            // manual stop resets the count.
+            let crashes = stats.crashes.load(Ordering::SeqCst);
+            if crashes < seen_crashes {
+                crash_restarts = 0;
+            } else if crashes > seen_crashes && pid.is_none() {
+                crash_restarts += 1;
+                restart_crashed(&app, crash_restarts);
+            }
+            seen_crashes = crashes;
+
+            let Some(pid) = pid else {
+                watched = None;
+                continue;
+            };
+            let current = match watched.as_mut() {
+                Some(w) if w.pid == pid => w,
+                _ => watched.insert(Watched::new(pid)),
+            };
+
+            if probe_health(&client, crate::resolve_gateway_port(&app)).await {
+                current.last_ok = Some((Instant::now(), SystemTime::now()));
+                current.reported_hung = false;
+                crash_restarts = 0;
+                continue;
+            }
+            if current.reported_hung || !current.is_hung(Instant::now()) {
+                continue;
+            }
+
+            let restarting = crate::get_config_internal(&app)
+                .ok()
+                .and_then(|c| c.restart_hung_gateway)
+                .unwrap_or(false);
+            let hung = GatewayHung {
+                pid,
+                uptime_secs: current.since.elapsed().as_secs(),
+                last_ok_epoch_secs: current
+                    .last_ok
+                    .and_then(|(_, at)| at.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())),
+                restarting,
+            };
+            tracing::warn!(
+                "Gateway (pid {}) is alive but not answering health probes",
+                pid
+            );
+            current.reported_hung = true;
+            let _ = app.emit("gateway-hung", hung);
+
+            if restarting {
+                restart_hung(&app).await;
+                watched = None;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crash_restart_delay_is_capped() {
+        assert!(crash_restart_delay(1).is_some());
+        assert!(crash_restart_delay(MAX_CRASH_RESTARTS).is_some());
+        assert_eq!(crash_restart_delay(MAX_CRASH_RESTARTS + 1), None);
+    }
+}
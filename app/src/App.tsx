@@ -7,7 +7,7 @@ import { MemoryView } from "./components/MemoryView";
 import { DebugView } from "./components/DebugView";
 import { SetupWizard } from "./components/SetupWizard";
 import { ConfirmDialog } from "./components/ConfirmDialog";
-import { DEFAULT_GATEWAY_PORT } from "./lib/utils";
+import { DEFAULT_GATEWAY_PORT, errorMessage } from "./lib/utils";
 import type { Message, ViewType, BrainStatus, DownloadProgress, MemoryEntry, GatewayMessage, StartupInfo } from "./lib/types";
 import "./App.css";
 
@@ -198,7 +198,7 @@ function App() {
       await invoke("download_model");
     } catch (err) {
       console.error("Model download failed:", err);
-      setDownloadError(String(err));
+      setDownloadError(errorMessage(err));
       setIsDownloading(false);
     }
   }, []);
@@ -354,7 +354,7 @@ function App() {
 
   const handleSetupComplete = async () => {
     try {
-      await invoke("update_config", { config: { setup_complete: true, theme: "dark" } });
+      await invoke("set_setup_complete", { complete: true });
     } catch (err) {
       console.error("Failed to save config:", err);
     }
@@ -1,6 +1,6 @@
 import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { formatBytes, calcProgressPercent } from "../lib/utils";
+import { formatBytes, calcProgressPercent, errorMessage } from "../lib/utils";
 import type { DownloadProgress } from "../lib/types";
 
 type CheckStatus = "not_checked" | "checking" | "found" | "missing" | "ready" | "error";
@@ -114,7 +114,7 @@ export function SetupWizard({
             setBrowserStatus("error");
         } catch (err) {
             console.error("Browser setup failed:", err);
-            setBrowserError(String(err));
+            setBrowserError(errorMessage(err));
             setBrowserStatus("error");
         }
     }, [gatewayPort]);
@@ -15,6 +15,43 @@ export interface DownloadProgress {
     total: number;
 }
 
+/** Returned by `get_model_status`. */
+export type ModelStatus =
+    | { state: "missing" }
+    /** `total` is 0 when the size isn't known yet. */
+    | { state: "partial"; downloaded: number; total: number }
+    | { state: "complete"; size: number };
+
+/** Returned by `get_model_info`, read from the model's GGUF header. */
+export interface ModelInfo {
+    version: number;
+    name: string | null;
+    architecture: string | null;
+    context_length: number | null;
+    quantization: string | null;
+    parameter_count: number;
+    tensor_count: number;
+}
+
+/** Returned by `list_models`. */
+export interface ModelListing {
+    models: { filename: string; size: number; is_active: boolean }[];
+    /** Unfinished downloads; `total` is 0 when unknown. */
+    partial: { filename: string; downloaded: number; total: number }[];
+}
+
+/** Returned by `get_storage_usage`, in bytes. */
+export interface StorageUsage {
+    models: number;
+    partial_downloads: number;
+    logs: number;
+    config: number;
+    other: number;
+    total: number;
+    /** The directory walk hit its bound, so the figures are a lower bound. */
+    truncated: boolean;
+}
+
 export type ViewType = "chat" | "memory" | "debug";
 
 export type BrainStatus = "ready" | "warming_up" | "error";
@@ -56,6 +93,32 @@ export interface StartupInfo {
     model_size: number;
     model_name: string;
     gateway_port: number;
+    /** Model unchanged since it last ran inference successfully. */
+    model_known_good: boolean;
+    /** Set when the model is on a network drive or in a cloud-synced folder. */
+    model_filesystem_warning: string | null;
+    /** `config.theme` with "system" resolved to the OS appearance. */
+    resolved_theme: string;
+}
+
+/** Error returned by every Tauri command. */
+export interface AppError {
+    kind:
+        | "io"
+        | "network"
+        | "config"
+        | "gateway"
+        | "download"
+        | "wrong_content_type"
+        | "wrong_content_length"
+        | "not_found"
+        | "unavailable"
+        | "busy"
+        | "timed_out"
+        | "cancelled"
+        | "paused"
+        | "other";
+    message: string;
 }
 
 export interface GatewayMessage {
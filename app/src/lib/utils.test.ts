@@ -1,5 +1,5 @@
 import { describe, it, expect } from "vitest";
-import { formatBytes, calcProgressPercent, formatUptime } from "./utils";
+import { formatBytes, calcProgressPercent, formatUptime, errorMessage } from "./utils";
 
 describe("formatBytes", () => {
     it("returns '0 GB' for zero bytes", () => {
@@ -61,3 +61,13 @@ describe("formatUptime", () => {
         expect(formatUptime(86400)).toBe("24h 0m 0s");
     });
 });
+
+describe("errorMessage", () => {
+    it("reads the message of a command error", () => {
+        expect(errorMessage({ kind: "busy", message: "Download in progress" })).toBe("Download in progress");
+    });
+
+    it("falls back to String() for anything else", () => {
+        expect(errorMessage("plain")).toBe("plain");
+    });
+});
@@ -2,7 +2,7 @@
  * Shared utility functions for the OpenMoose frontend.
  */
 
-import type { DownloadProgress } from "./types";
+import type { AppError, DownloadProgress } from "./types";
 
 /**
  * Formats bytes into a human-readable GB string using the decimal standard (1000^3).
@@ -45,6 +45,17 @@ export const copyToClipboard = async (text: string): Promise<boolean> => {
     }
 };
 
+/**
+ * Human-readable message for an error thrown by `invoke`, which rejects with
+ * an `AppError` object rather than a string.
+ */
+export const errorMessage = (err: unknown): string => {
+    if (err && typeof err === "object" && "message" in err) {
+        return String((err as AppError).message);
+    }
+    return String(err);
+};
+
 /**
  * Common constants.
  */
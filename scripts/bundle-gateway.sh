@@ -103,5 +103,16 @@ if [ -d "$REPO_ROOT/skills" ]; then
   cp -r "$REPO_ROOT/skills" "$RESOURCES_DIR/"
 fi
 
+# Record a hash manifest of the final bundle. build.rs embeds it into the app
+# so verify_install_integrity can detect tampered or partially-copied installs.
+echo "==> Writing integrity manifest..."
+cd "$RESOURCES_DIR"
+if command -v sha256sum >/dev/null 2>&1; then
+  HASH_CMD="sha256sum"
+else
+  HASH_CMD="shasum -a 256"
+fi
+find . -type f -print0 | LC_ALL=C sort -z | xargs -0 $HASH_CMD > "$REPO_ROOT/app/src-tauri/resources/gateway-manifest.sha256"
+
 echo "==> Gateway bundled into: $RESOURCES_DIR"
 echo "    New Contents Size: $(du -sh "$RESOURCES_DIR" | cut -f1)"
@@ -10,7 +10,8 @@ import { readFileSync, existsSync } from 'node:fs';
 dotenv.config();
 
 const mooseHome = process.env.MOOSE_HOME || path.join(process.env.HOME || process.env.USERPROFILE || process.cwd(), '.moose');
-const configJsonPath = path.join(mooseHome, 'config.json');
+// The app passes MOOSE_CONFIG_PATH because config.json can live outside MOOSE_HOME.
+const configJsonPath = process.env.MOOSE_CONFIG_PATH || path.join(mooseHome, 'config.json');
 
 let jsonConfig: Record<string, unknown> = {};
 try {